@@ -0,0 +1,28 @@
+use spell::Spell;
+
+// Things that can be done to a cell from the right click menu
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    // Monster uuid
+    Attack(usize),
+    Target(usize),
+    CastSpell(Spell),
+    Examine,
+    Travel,
+    LootCorpse,
+    OpenDoor
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match *self {
+            Action::Attack(_) => String::from("Attack"),
+            Action::Target(_) => String::from("Target"),
+            Action::CastSpell(spell) => format!("Cast {} ({} mana)", spell.name(), spell.mana_cost()),
+            Action::Examine => String::from("Examine"),
+            Action::Travel => String::from("Travel here"),
+            Action::LootCorpse => String::from("Loot corpse"),
+            Action::OpenDoor => String::from("Open door")
+        }
+    }
+}
@@ -0,0 +1,224 @@
+extern crate rusty_rogue;
+
+use std::env;
+use std::panic;
+use std::process;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use rusty_rogue::{Dungeon, DungeonProperties, FloorStats, Vec2};
+
+// Builds a run of dungeons from consecutive seeds and prints how
+// they came out. Same seed and properties always give the same dungeon.
+// --dot and --ascii print the one dungeon from SEED instead, for
+// Graphviz or pasting into a bug report
+//
+// dungeon_stats [count] [first seed] [--rooms MIN MAX] [--grid MIN MAX]
+//               [--room-size MIN MAX] [--depth MIN MAX] [--loops N]
+//               [--dot SEED] [--ascii SEED]
+
+const USAGE : &str = "usage: dungeon_stats [count] [first seed] [--rooms MIN MAX] [--grid MIN MAX] \
+                      [--room-size MIN MAX] [--depth MIN MAX] [--loops N] [--dot SEED] [--ascii SEED]";
+
+// Widest a histogram bar gets
+const BAR_WIDTH : usize = 40;
+
+thread_local! {
+    // Filled in by the panic hook so failures can say where they happened
+    static LAST_PANIC : RefCell<String> = RefCell::new(String::new());
+}
+
+enum Export {
+    Dot,
+    Ascii
+}
+
+struct Options {
+    count : u64,
+    first_seed : u64,
+    properties : DungeonProperties,
+    export : Option<(Export, u64)>
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(1);
+        }
+    };
+
+    if let Some((ref export, seed)) = options.export {
+        rusty_rogue::seed(seed);
+        let dungeon = match Dungeon::generate(&options.properties) {
+            Ok(dungeon) => dungeon,
+            Err(e) => {
+                eprintln!("seed {} failed: {}", seed, e);
+                process::exit(1);
+            }
+        };
+
+        match *export {
+            Export::Dot => print!("{}", dungeon.floor_dot()),
+            Export::Ascii => print!("{}", dungeon.floor_ascii())
+        }
+        return;
+    }
+
+    // Panics are reported with the seed that caused them instead
+    panic::set_hook(Box::new(|info| {
+        let message = match info.payload().downcast_ref::<&str>() {
+            Some(s) => s.to_string(),
+            None => match info.payload().downcast_ref::<String>() {
+                Some(s) => s.clone(),
+                None => String::from("unknown panic")
+            }
+        };
+        let location = match info.location() {
+            Some(l) => format!(" at {}:{}", l.file(), l.line()),
+            None => String::new()
+        };
+        LAST_PANIC.with(|p| *p.borrow_mut() = format!("{}{}", message, location));
+    }));
+
+    let mut stats = Vec::new();
+    let mut failures = Vec::new();
+    let last_seed = options.first_seed + options.count;
+    for seed in options.first_seed..last_seed {
+        rusty_rogue::seed(seed);
+        let properties = &options.properties;
+        let result = panic::catch_unwind(|| -> Result<FloorStats, String> {
+            let dungeon = Dungeon::generate(properties)?;
+            dungeon.validate()?;
+            Ok(dungeon.floor_stats())
+        });
+
+        match result {
+            Ok(Ok(floor)) => stats.push(floor),
+            Ok(Err(e)) => failures.push(format!("seed {} failed: {}", seed, e)),
+            Err(_) => {
+                let message = LAST_PANIC.with(|p| p.borrow().clone());
+                failures.push(format!("seed {} panicked: {}", seed, message));
+            }
+        }
+    }
+
+    println!("Generated {} dungeons from seeds {} to {}, {} failed",
+             options.count, options.first_seed, last_seed.saturating_sub(1), failures.len());
+    for failure in &failures {
+        println!("  {}", failure);
+    }
+    if stats.is_empty() {
+        return;
+    }
+
+    let rooms : Vec<usize> = stats.iter().map(|s| s.rooms).collect();
+    let widths : Vec<usize> = stats.iter().flat_map(|s| s.room_sizes.iter().map(|&(w, _)| w)).collect();
+    let heights : Vec<usize> = stats.iter().flat_map(|s| s.room_sizes.iter().map(|&(_, h)| h)).collect();
+    let goblins : Vec<usize> = stats.iter().flat_map(|s| s.goblins.iter().cloned()).collect();
+    let diameters : Vec<usize> = stats.iter().map(|s| s.diameter).collect();
+    let dead_ends : Vec<usize> = stats.iter().map(|s| s.dead_ends).collect();
+
+    report("Rooms per dungeon", &rooms);
+    report("Room width", &widths);
+    report("Room height", &heights);
+    report("Goblins per room", &goblins);
+    report("Graph diameter", &diameters);
+    report("Dead ends per dungeon", &dead_ends);
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn parse_args(args : Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        count : 100,
+        first_seed : 0,
+        properties : DungeonProperties::new(1),
+        export : None
+    };
+
+    let mut positional = 0;
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        // How many numbers the flag takes
+        let takes = match arg {
+            "--rooms" | "--grid" | "--room-size" | "--depth" => 2,
+            "--loops" | "--dot" | "--ascii" => 1,
+            _ => 0
+        };
+
+        if takes == 0 {
+            let value = number(arg)?;
+            match positional {
+                0 => options.count = value as u64,
+                1 => options.first_seed = value as u64,
+                _ => return Err(format!("Unexpected argument {}", arg))
+            }
+            positional += 1;
+            i += 1;
+            continue;
+        }
+
+        if i + takes >= args.len() {
+            let missing = match arg {
+                "--dot" | "--ascii" => "seed",
+                _ if takes == 2 => "min and max",
+                _ => "value"
+            };
+            return Err(format!("{} is missing its {}", arg, missing));
+        }
+        let a = number(&args[i + 1])?;
+        let b = if takes == 2 { number(&args[i + 2])? } else { 0 };
+
+        if arg == "--dot" || arg == "--ascii" {
+            let export = if arg == "--dot" { Export::Dot } else { Export::Ascii };
+            options.export = Some((export, a as u64));
+            i += takes + 1;
+            continue;
+        }
+
+        let properties = options.properties;
+        options.properties = match arg {
+            "--rooms" => properties.with_rooms(a, b),
+            "--grid" => properties.with_grid_size(Vec2::new(a, a), Vec2::new(b, b)),
+            "--room-size" => properties.with_room_size(Vec2::new(a, a), Vec2::new(b, b)),
+            "--depth" => properties.with_depth(a, b),
+            _ => properties.with_loops(a)
+        };
+        i += takes + 1;
+    }
+
+    Ok(options)
+}
+
+fn number(arg : &str) -> Result<usize, String> {
+    arg.parse::<usize>().map_err(|_| format!("{} isn't a number", arg))
+}
+
+// Min, mean and max then a bar for every value seen
+fn report(name : &str, values : &Vec<usize>) {
+    println!("");
+    if values.is_empty() {
+        println!("{}: none", name);
+        return;
+    }
+
+    let min = values.iter().min().unwrap();
+    let max = values.iter().max().unwrap();
+    let mean = values.iter().sum::<usize>() as f64 / values.len() as f64;
+    println!("{}: min {} mean {:.2} max {}", name, min, mean, max);
+
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(*value).or_insert(0) += 1;
+    }
+
+    let most = *counts.values().max().unwrap();
+    for (value, count) in &counts {
+        let bar = (count * BAR_WIDTH + most - 1) / most;
+        println!("  {:>4} | {:<width$} {}", value, "#".repeat(bar), count, width = BAR_WIDTH);
+    }
+}
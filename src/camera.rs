@@ -0,0 +1,81 @@
+use utils::Vec2;
+
+// Maps room coordinates to viewport coordinates and back.
+// Rooms that fit are centered in the viewport. Rooms that are bigger
+// scroll so the player stays in the middle until an edge is reached
+pub struct Camera {
+    viewport : Vec2<usize>,
+    room_size : Vec2<usize>,
+    // First room tile shown in the viewport
+    origin : Vec2<usize>,
+    // Where in the viewport the room starts drawing
+    offset : Vec2<usize>
+}
+
+impl Camera {
+    pub fn new(viewport : Vec2<usize>) -> Camera {
+        Camera {
+            viewport,
+            room_size : Vec2::new(0, 0),
+            origin : Vec2::new(0, 0),
+            offset : Vec2::new(0, 0)
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport : Vec2<usize>) {
+        self.viewport = viewport;
+    }
+
+    pub fn update(&mut self, room_size : Vec2<usize>, focus : Vec2<usize>) {
+        self.room_size = room_size;
+
+        let (origin_x, offset_x) = axis(self.viewport.x, room_size.x, focus.x);
+        let (origin_y, offset_y) = axis(self.viewport.y, room_size.y, focus.y);
+        self.origin = Vec2::new(origin_x, origin_y);
+        self.offset = Vec2::new(offset_x, offset_y);
+    }
+
+    // None if the tile is scrolled out of view
+    pub fn to_screen(&self, pos : Vec2<usize>) -> Option<Vec2<usize>> {
+        if pos.x < self.origin.x || pos.y < self.origin.y {
+            return None;
+        }
+
+        let screen = Vec2::new(pos.x - self.origin.x + self.offset.x,
+                               pos.y - self.origin.y + self.offset.y);
+        if screen.x >= self.viewport.x || screen.y >= self.viewport.y {
+            return None;
+        }
+
+        Some(screen)
+    }
+
+    // None if the screen position isnt over the room
+    pub fn to_room(&self, screen : Vec2<usize>) -> Option<Vec2<usize>> {
+        if screen.x < self.offset.x || screen.y < self.offset.y ||
+           screen.x >= self.viewport.x || screen.y >= self.viewport.y 
+        {
+            return None;
+        }
+
+        let pos = Vec2::new(screen.x - self.offset.x + self.origin.x,
+                            screen.y - self.offset.y + self.origin.y);
+        if pos.x >= self.room_size.x || pos.y >= self.room_size.y {
+            return None;
+        }
+
+        Some(pos)
+    }
+}
+
+// Returns the (origin, offset) pair for one axis
+fn axis(viewport : usize, room : usize, focus : usize) -> (usize, usize) {
+    if room <= viewport {
+        return (0, (viewport - room) / 2);
+    }
+
+    let half = viewport / 2;
+    let max_origin = room - viewport;
+    let origin = if focus < half { 0 } else { (focus - half).min(max_origin) };
+    (origin, 0)
+}
@@ -1,6 +1,7 @@
 use utils::Vec2;
 use entity::StatBlock;
-use entity::EntityMap;
+use entity::{StatusEffect, StatusEffects};
+use entity::Entity;
 
 use world::Tile;
 use player::Player;
@@ -16,13 +17,17 @@ pub enum ElementalType {
 pub enum AttackType {
     Slashing,
     Piercing,
-    Elemental(ElementalType)
+    Elemental(ElementalType),
+    // Nothing resists it, for damage that's already been let in
+    Untyped
 }
 
 pub struct Attack {
     pub attack_type : AttackType,
     pub damage : i32,
-    pub position : Vec2<usize>
+    pub position : Vec2<usize>,
+    // What sticks around after the hit
+    pub status : Option<StatusEffect>
 }
 
 pub struct CombatResult {
@@ -39,7 +44,19 @@ impl Attack {
         Attack {
             attack_type,
             damage,
-            position
+            position,
+            status : StatusEffect::from_attack(attack_type, damage)
+        }
+    }
+
+    // Damage from something already burning/poisoning you.
+    // Doesnt set anything new off
+    pub fn over_time(damage : i32, position : Vec2<usize>) -> Attack {
+        Attack {
+            attack_type : AttackType::Untyped,
+            damage,
+            position,
+            status : None
         }
     }
 }
@@ -51,8 +68,28 @@ pub trait Attackable {
 
     // Getters
     fn position(&self) -> &Vec2<usize>;
+    // Only for moving between rooms, normal movement happens in update
+    fn set_position(&mut self, pos : Vec2<usize>);
     fn alive(&self) -> bool;
     fn base_stats(&self) -> &StatBlock;
     fn current_stats(&self) -> &StatBlock;
     fn name(&self) -> &str;
+    fn status(&self) -> &StatusEffects;
+    fn status_mut(&mut self) -> &mut StatusEffects;
+
+    // Called once a turn, each kind heals at its own pace
+    fn regenerate(&mut self);
+    fn heal(&mut self, amount : i32);
+
+    // Healing handed out to everything else within range
+    // this turn as (amount, range). Only shamans bother
+    fn heal_allies(&mut self) -> Option<(i32, usize)> {
+        None
+    }
+
+    // Anything that should be added to the room at pos after 
+    // a turn, ie a slime that got hit and split in two
+    fn split(&mut self, _pos : Vec2<usize>) -> Option<Box<Entity>> {
+        None
+    }
 }
\ No newline at end of file
@@ -1,17 +1,22 @@
 use std::collections::HashMap;
 use utils::Vec2;
 use utils::Vec3;
-use entity::{DrawOutput};
+use entity::{Drawable, DrawOutput};
 
 pub struct Corpse {
-    pos : Vec2<usize>
+    pos : Vec2<usize>,
+    looted : bool,
+    // Turns since it died
+    age : usize
 }
 pub type CorpseMap = HashMap<usize, Corpse>;
 
 impl Corpse {
     pub fn new(pos : Vec2<usize>) -> Corpse {
         Corpse {
-            pos
+            pos,
+            looted : false,
+            age : 0
         }
     }
 
@@ -19,7 +24,25 @@ impl Corpse {
         &self.pos
     }
 
-    pub fn draw(&self) -> DrawOutput {
+    pub fn looted(&self) -> bool {
+        self.looted
+    }
+
+    pub fn set_looted(&mut self) {
+        self.looted = true;
+    }
+
+    pub fn age(&self) -> usize {
+        self.age
+    }
+
+    pub fn grow_older(&mut self, turns : usize) {
+        self.age += turns;
+    }
+}
+
+impl Drawable for Corpse {
+    fn draw(&self) -> DrawOutput {
         DrawOutput {
             position : self.pos,
             fg : Vec3::new(50, 50, 50),
@@ -27,4 +50,8 @@ impl Corpse {
             icon : 'c'
         }
     }
+
+    fn kind(&self) -> &str {
+        "corpse"
+    }
 }
@@ -1,6 +1,7 @@
 use utils::Vec3;
 use utils::Vec2;
 
+#[derive(Debug, Copy, Clone)]
 pub struct DrawOutput {
     pub position : Vec2<usize>,
     pub fg : Vec3<u8>,
@@ -10,9 +11,103 @@ pub struct DrawOutput {
 
 pub trait Drawable {
     fn draw(&self) -> DrawOutput;
+
+    // Key used to look the entity up in a theme file
+    fn kind(&self) -> &str;
+}
+
+// Things get drawn bottom to top in this order, so a monster
+// standing on an item hides the item
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Tile,
+    Corpse,
+    Item,
+    Effect,
+    Monster,
+    Player
+}
+
+// What ends up on a cell once the layers are sorted out. 
+// stack is how many things (not counting the tile) share the cell
+#[derive(Debug, Copy, Clone)]
+pub struct DrawCell {
+    pub output : DrawOutput,
+    pub layer : Layer,
+    pub stack : usize
+}
+
+impl DrawCell {
+    // More than one thing is sitting here
+    pub fn stacked(&self) -> bool {
+        self.stack > 1
+    }
+}
+
+// Built up fresh each frame. Everything that wants to be drawn
+// gets pushed with its layer, then each cell is resolved to
+// whatever sits on top
+pub struct DrawManager {
+    size : Vec2<usize>,
+    cells : Vec<Vec<(Layer, DrawOutput)>>
 }
 
-// SOme draw manager that holds a list of Drawers
-// A Drawer would be like GoblinDrawer 
-// I would have a temporary list built up each step
-// with a position and some meta information
\ No newline at end of file
+impl DrawManager {
+    pub fn new(size : Vec2<usize>) -> DrawManager {
+        let mut cells = Vec::new();
+        for _ in 0..size.x*size.y {
+            cells.push(Vec::new());
+        }
+
+        DrawManager {
+            size,
+            cells
+        }
+    }
+
+    // Anything outside the area is dropped
+    pub fn push(&mut self, layer : Layer, output : DrawOutput) {
+        let pos = output.position;
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return;
+        }
+
+        self.cells[pos.x + pos.y * self.size.x].push((layer, output));
+    }
+
+    // Top most output on a cell. Within a layer the last one pushed wins
+    pub fn resolve(&self, pos : Vec2<usize>) -> Option<DrawCell> {
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+
+        let cell = &self.cells[pos.x + pos.y * self.size.x];
+        let mut top : Option<&(Layer, DrawOutput)> = None;
+        for entry in cell.iter() {
+            match top {
+                Some(t) if t.0 > entry.0 => {},
+                _ => top = Some(entry)
+            }
+        }
+
+        top.map(|&(layer, output)| DrawCell {
+            output,
+            layer,
+            stack : cell.iter().filter(|e| e.0 != Layer::Tile).count()
+        })
+    }
+
+    // Every cell with something on it, in row order
+    pub fn cells(&self) -> Vec<DrawCell> {
+        let mut out = Vec::new();
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                if let Some(cell) = self.resolve(Vec2::new(x, y)) {
+                    out.push(cell);
+                }
+            }
+        }
+
+        out
+    }
+}
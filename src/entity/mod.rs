@@ -3,9 +3,11 @@ mod display;
 mod entity;
 mod stats;
 mod corpse;
+mod status;
 
-pub use self::attack::{Attackable, Attack, CombatResult, AttackType};
+pub use self::attack::{Attackable, Attack, CombatResult, AttackType, ElementalType};
 pub use self::entity::{Entity, EntityMap};
 pub use self::corpse::{Corpse, CorpseMap};
-pub use self::stats::{StatBlock, Facing};
-pub use self::display::{Drawable, DrawOutput};
\ No newline at end of file
+pub use self::stats::{StatBlock, Facing, Regeneration};
+pub use self::status::{StatusEffect, StatusEffects, StatusKind};
+pub use self::display::{Drawable, DrawOutput, DrawManager, DrawCell, Layer};
\ No newline at end of file
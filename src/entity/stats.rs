@@ -40,4 +40,48 @@ impl StatBlock {
             mana : intelligence * 2
         }
     }
-}
\ No newline at end of file
+
+    pub fn add(&mut self, other : &StatBlock) {
+        self.strength += other.strength;
+        self.dexterity += other.dexterity;
+        self.intelligence += other.intelligence;
+        self.health += other.health;
+        self.mana += other.mana;
+    }
+
+    pub fn sub(&mut self, other : &StatBlock) {
+        self.strength -= other.strength;
+        self.dexterity -= other.dexterity;
+        self.intelligence -= other.intelligence;
+        self.health -= other.health;
+        self.mana -= other.mana;
+    }
+}
+
+// Regen is a fraction of a point each turn so it builds up
+// here until there's a whole point to hand out.
+// Rates are in hundredths of a point per turn
+pub struct Regeneration {
+    health : i32,
+    mana : i32
+}
+
+impl Regeneration {
+    pub fn new() -> Regeneration {
+        Regeneration {
+            health : 0,
+            mana : 0
+        }
+    }
+
+    pub fn tick(&mut self, curr : &mut StatBlock, base : &StatBlock, health_rate : i32, mana_rate : i32) {
+        self.health += health_rate;
+        self.mana += mana_rate;
+
+        curr.health = (curr.health + self.health / 100).min(base.health).max(curr.health);
+        curr.mana = (curr.mana + self.mana / 100).min(base.mana).max(curr.mana);
+
+        self.health %= 100;
+        self.mana %= 100;
+    }
+}
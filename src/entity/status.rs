@@ -0,0 +1,164 @@
+use entity::{AttackType, ElementalType};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatusKind {
+    Burning,
+    Frozen,
+    Shocked,
+    Poisoned,
+    Stunned
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &str {
+        match *self {
+            StatusKind::Burning => "Burning",
+            StatusKind::Frozen => "Frozen",
+            StatusKind::Shocked => "Shocked",
+            StatusKind::Poisoned => "Poisoned",
+            StatusKind::Stunned => "Stunned"
+        }
+    }
+}
+
+// potency is damage per turn for burning/poison,
+// the rest only care about how long they last
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StatusEffect {
+    pub kind : StatusKind,
+    pub turns : usize,
+    pub potency : i32
+}
+
+impl StatusEffect {
+    pub fn new(kind : StatusKind, turns : usize, potency : i32) -> StatusEffect {
+        StatusEffect {
+            kind,
+            turns,
+            potency
+        }
+    }
+
+    // Elemental hits leave something behind
+    pub fn from_attack(attack_type : AttackType, damage : i32) -> Option<StatusEffect> {
+        match attack_type {
+            AttackType::Elemental(ElementalType::Fire) =>
+                Some(StatusEffect::new(StatusKind::Burning, 3, (damage / 4).max(1))),
+            AttackType::Elemental(ElementalType::Frost) =>
+                Some(StatusEffect::new(StatusKind::Frozen, 3, 0)),
+            AttackType::Elemental(ElementalType::Lightning) =>
+                Some(StatusEffect::new(StatusKind::Shocked, 2, 0)),
+            _ => None
+        }
+    }
+}
+
+// Everything currently affecting a player or monster
+pub struct StatusEffects {
+    effects : Vec<StatusEffect>,
+    // Frozen things only get every other turn, starting with
+    // the one after they froze. Flipped once a turn by tick
+    thawing : bool
+}
+
+impl StatusEffects {
+    pub fn new() -> StatusEffects {
+        StatusEffects {
+            effects : Vec::new(),
+            thawing : false
+        }
+    }
+
+    // Stacking rules:
+    // Poison piles up, everything else just refreshes the duration.
+    // Fire and ice cancel each other out and a second shock stuns
+    pub fn apply(&mut self, effect : StatusEffect) {
+        match effect.kind {
+            StatusKind::Burning => {
+                if self.has(StatusKind::Frozen) {
+                    self.remove(StatusKind::Frozen);
+                    return;
+                }
+            },
+            StatusKind::Frozen => {
+                if self.has(StatusKind::Burning) {
+                    self.remove(StatusKind::Burning);
+                    return;
+                }
+            },
+            StatusKind::Shocked => {
+                if self.has(StatusKind::Shocked) {
+                    self.remove(StatusKind::Shocked);
+                    self.apply(StatusEffect::new(StatusKind::Stunned, 1, 0));
+                    return;
+                }
+            },
+            _ => {}
+        }
+
+        if let Some(current) = self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            current.turns = current.turns.max(effect.turns);
+            if let StatusKind::Poisoned = effect.kind {
+                current.potency += effect.potency;
+            }
+            else {
+                current.potency = current.potency.max(effect.potency);
+            }
+            return;
+        }
+
+        self.effects.push(effect);
+    }
+
+    pub fn has(&self, kind : StatusKind) -> bool {
+        self.effects.iter().any(|e| e.kind == kind)
+    }
+
+    pub fn remove(&mut self, kind : StatusKind) {
+        self.effects.retain(|e| e.kind != kind);
+    }
+
+    pub fn effects(&self) -> &Vec<StatusEffect> {
+        &self.effects
+    }
+
+    // Whether this turn can be used. Asking doesn't use it up,
+    // the turn only moves on in tick
+    pub fn can_act(&self) -> bool {
+        if self.has(StatusKind::Stunned) {
+            return false;
+        }
+
+        !self.has(StatusKind::Frozen) || self.thawing
+    }
+
+    // Shocked things can't hit as hard
+    pub fn scale_damage(&self, damage : i32) -> i32 {
+        if self.has(StatusKind::Shocked) {
+            return (damage / 2).max(1);
+        }
+        damage
+    }
+
+    // Counts every effect down a turn and returns the damage they did
+    pub fn tick(&mut self) -> i32 {
+        let mut damage = 0;
+        for effect in &mut self.effects {
+            match effect.kind {
+                StatusKind::Burning | StatusKind::Poisoned => damage += effect.potency,
+                _ => {}
+            }
+            effect.turns = effect.turns.saturating_sub(1);
+        }
+
+        self.effects.retain(|e| e.turns > 0);
+        if self.has(StatusKind::Frozen) {
+            self.thawing = !self.thawing;
+        }
+        else {
+            self.thawing = false;
+        }
+
+        damage
+    }
+}
@@ -3,15 +3,20 @@ use utils::Vec2;
 use utils::Vec3;
 use world::Tile;
 use entity::{Attackable, Attack, AttackType, CombatResult, 
-             StatBlock, Facing, Drawable, DrawOutput};
+             StatBlock, Facing, Drawable, DrawOutput, StatusEffects, 
+             Regeneration};
 use super::player::Player;
 
+// Hundredths of a health point a turn
+const REGEN_RATE : i32 = 10;
 
 pub struct Goblin {
     pos : Vec2<usize>,
     base_stats : StatBlock,
     curr_stats : StatBlock,
     facing : Facing,
+    status : StatusEffects,
+    regen : Regeneration,
     alive : bool
 }
 
@@ -22,6 +27,8 @@ impl Goblin {
             base_stats : StatBlock::new(2,2,2),
             curr_stats : StatBlock::new(2,2,2),
             facing : Facing::North,
+            status : StatusEffects::new(),
+            regen : Regeneration::new(),
             alive : true
         }
     }
@@ -34,7 +41,7 @@ impl Attackable for Goblin {
         let diff = p_pos - m_pos;
         if diff.x.abs().bitxor(diff.y.abs()) == 1 {
             let atk_pos = self.facing.position(self.pos);
-            let damage = self.curr_stats.strength;
+            let damage = self.status.scale_damage(self.curr_stats.strength);
             return Some(Attack::new(AttackType::Piercing, damage, atk_pos));
         }
         else {
@@ -53,12 +60,12 @@ impl Attackable for Goblin {
 
             if diff.y.abs() > diff.x.abs() &&
                new_pos.y > 0 && 
-               !tiles[self.pos.x + new_pos.y as usize * room_size.x].occupied 
+               tiles[self.pos.x + new_pos.y as usize * room_size.x].walkable() 
             {
                 self.pos.y = new_pos.y as usize;
             }
             else if new_pos.x > 0 && 
-               !tiles[new_pos.x as usize + self.pos.y * room_size.x].occupied 
+               tiles[new_pos.x as usize + self.pos.y * room_size.x].walkable() 
             {
                 self.pos.x = new_pos.x as usize;
             }
@@ -72,6 +79,10 @@ impl Attackable for Goblin {
         if self.curr_stats.health <= 0 {
             self.alive = false;
         }
+
+        if let Some(effect) = attack.status {
+            self.status.apply(effect);
+        }
         
         CombatResult {
             dmg_dealt : attack.damage,
@@ -84,6 +95,10 @@ impl Attackable for Goblin {
         &self.pos
     }
 
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.pos = pos;
+    }
+
     fn collision(&self, other : Vec2<usize>) -> bool {
         self.alive && other == self.pos
     }
@@ -103,6 +118,22 @@ impl Attackable for Goblin {
     fn name(&self) -> &str {
         "a Goblin"
     }
+
+    fn status(&self) -> &StatusEffects {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        &mut self.status
+    }
+
+    fn regenerate(&mut self) {
+        self.regen.tick(&mut self.curr_stats, &self.base_stats, REGEN_RATE, 0);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.curr_stats.health = (self.curr_stats.health + amount).min(self.base_stats.health);
+    }
 }
 
 impl Drawable for Goblin {
@@ -117,4 +148,8 @@ impl Drawable for Goblin {
             icon : 'g'
         }
     }
+
+    fn kind(&self) -> &str {
+        "goblin"
+    }
 }
\ No newline at end of file
@@ -24,6 +24,9 @@ pub enum Input {
     Up,
     Down,
     Key(char),
+    Escape,
+    Tab,
+    Enter,
     Mouse {
         offset: Vec2<usize>,
         position: Vec2<usize>,
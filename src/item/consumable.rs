@@ -0,0 +1,54 @@
+extern crate rand;
+
+use self::rand::prelude::*;
+use utils::rng;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Consumable {
+    HealingPotion,
+    ManaPotion,
+    TeleportScroll,
+    MappingScroll,
+    Food
+}
+
+// Out of 10, how likely each one is to show up
+const DROP_WEIGHTS : [(Consumable, usize); 5] = [
+    (Consumable::HealingPotion, 3),
+    (Consumable::ManaPotion, 2),
+    (Consumable::TeleportScroll, 1),
+    (Consumable::MappingScroll, 1),
+    (Consumable::Food, 3)
+];
+
+impl Consumable {
+    pub fn random() -> Consumable {
+        let mut roll = rng().gen_range(0, 10);
+        for &(consumable, weight) in DROP_WEIGHTS.iter() {
+            if roll < weight {
+                return consumable;
+            }
+            roll -= weight;
+        }
+
+        Consumable::Food
+    }
+
+    pub fn name(&self) -> &str {
+        match *self {
+            Consumable::HealingPotion => "healing potion",
+            Consumable::ManaPotion => "mana potion",
+            Consumable::TeleportScroll => "scroll of teleport",
+            Consumable::MappingScroll => "scroll of magic mapping",
+            Consumable::Food => "ration"
+        }
+    }
+
+    pub fn icon(&self) -> char {
+        match *self {
+            Consumable::HealingPotion | Consumable::ManaPotion => '!',
+            Consumable::TeleportScroll | Consumable::MappingScroll => '?',
+            Consumable::Food => '%'
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use entity::AttackType;
+use item::{Gear, GearSlot};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EquipSlot {
+    Weapon,
+    Offhand,
+    Head,
+    Body,
+    Feet,
+    LeftRing,
+    RightRing
+}
+
+const SLOTS : [EquipSlot; 7] = [
+    EquipSlot::Weapon,
+    EquipSlot::Offhand,
+    EquipSlot::Head,
+    EquipSlot::Body,
+    EquipSlot::Feet,
+    EquipSlot::LeftRing,
+    EquipSlot::RightRing
+];
+
+impl EquipSlot {
+    pub fn all() -> &'static [EquipSlot] {
+        &SLOTS
+    }
+
+    pub fn name(&self) -> &str {
+        match *self {
+            EquipSlot::Weapon => "Weapon",
+            EquipSlot::Offhand => "Offhand",
+            EquipSlot::Head => "Head",
+            EquipSlot::Body => "Body",
+            EquipSlot::Feet => "Feet",
+            EquipSlot::LeftRing => "Ring",
+            EquipSlot::RightRing => "Ring"
+        }
+    }
+
+    fn index(&self) -> usize {
+        SLOTS.iter().position(|s| s == self).unwrap()
+    }
+
+    fn fits(&self, slot : GearSlot) -> bool {
+        match (*self, slot) {
+            (EquipSlot::Weapon, GearSlot::Weapon) |
+            (EquipSlot::Offhand, GearSlot::Offhand) |
+            (EquipSlot::Head, GearSlot::Head) |
+            (EquipSlot::Body, GearSlot::Body) |
+            (EquipSlot::Feet, GearSlot::Feet) |
+            (EquipSlot::LeftRing, GearSlot::Ring) |
+            (EquipSlot::RightRing, GearSlot::Ring) => true,
+            _ => false
+        }
+    }
+}
+
+// Everything the player is wearing
+pub struct Equipment {
+    slots : Vec<Option<Gear>>
+}
+
+impl Equipment {
+    pub fn new() -> Equipment {
+        let mut slots = Vec::new();
+        for _ in SLOTS.iter() {
+            slots.push(None);
+        }
+
+        Equipment {
+            slots
+        }
+    }
+
+    pub fn get(&self, slot : EquipSlot) -> Option<&Gear> {
+        self.slots[slot.index()].as_ref()
+    }
+
+    pub fn weapon(&self) -> Option<&Gear> {
+        self.get(EquipSlot::Weapon)
+    }
+
+    // Puts the gear in the first empty slot it fits, or swaps out 
+    // the last one. Hands back whatever was taken off
+    pub fn equip(&mut self, gear : Gear) -> Option<Gear> {
+        let fits : Vec<EquipSlot> = SLOTS.iter()
+                                         .cloned()
+                                         .filter(|s| s.fits(gear.slot()))
+                                         .collect();
+        let slot = match fits.iter().find(|s| self.get(**s).is_none()) {
+            Some(slot) => *slot,
+            None => *fits.last().unwrap()
+        };
+
+        let old = self.slots[slot.index()].take();
+        self.slots[slot.index()] = Some(gear);
+        old
+    }
+
+    pub fn unequip(&mut self, slot : EquipSlot) -> Option<Gear> {
+        self.slots[slot.index()].take()
+    }
+
+    pub fn resistance(&self, attack_type : AttackType) -> i32 {
+        self.slots.iter()
+                  .filter_map(|s| s.as_ref())
+                  .map(|g| g.resistance(attack_type))
+                  .sum()
+    }
+}
@@ -0,0 +1,118 @@
+use entity::{AttackType, ElementalType, StatBlock};
+use player::Class;
+
+// What kind of slot a piece of gear goes in.
+// Rings fit either ring slot
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GearSlot {
+    Weapon,
+    Offhand,
+    Head,
+    Body,
+    Feet,
+    Ring
+}
+
+pub struct Gear {
+    name : String,
+    slot : GearSlot,
+    bonus : StatBlock,
+    // Only weapons care about these
+    damage : i32,
+    attack_type : AttackType,
+    // Flat damage taken off hits of the matching type
+    resistances : Vec<(AttackType, i32)>
+}
+
+impl Gear {
+    pub fn new(name : &str, slot : GearSlot) -> Gear {
+        Gear {
+            name : String::from(name),
+            slot,
+            bonus : StatBlock::new(0, 0, 0),
+            damage : 0,
+            attack_type : AttackType::Piercing,
+            resistances : Vec::new()
+        }
+    }
+
+    pub fn weapon(name : &str, attack_type : AttackType, damage : i32) -> Gear {
+        let mut gear = Gear::new(name, GearSlot::Weapon);
+        gear.attack_type = attack_type;
+        gear.damage = damage;
+        gear
+    }
+
+    pub fn with_bonus(mut self, bonus : StatBlock) -> Gear {
+        self.bonus = bonus;
+        self
+    }
+
+    pub fn with_resistance(mut self, attack_type : AttackType, amount : i32) -> Gear {
+        self.resistances.push((attack_type, amount));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn slot(&self) -> GearSlot {
+        self.slot
+    }
+
+    pub fn bonus(&self) -> &StatBlock {
+        &self.bonus
+    }
+
+    pub fn damage(&self) -> i32 {
+        self.damage
+    }
+
+    pub fn attack_type(&self) -> AttackType {
+        self.attack_type
+    }
+
+    pub fn resistance(&self, attack_type : AttackType) -> i32 {
+        self.resistances.iter()
+                        .filter(|r| r.0 == attack_type)
+                        .map(|r| r.1)
+                        .sum()
+    }
+}
+
+// What each class walks in with. The first few get equipped,
+// anything that doesnt fit ends up in the pack
+pub fn starting_kit(class : Class) -> Vec<Gear> {
+    let mut kit = match class {
+        Class::Warrior => vec![
+            Gear::weapon("Short sword", AttackType::Slashing, 8),
+            Gear::new("Wooden shield", GearSlot::Offhand)
+                .with_resistance(AttackType::Slashing, 1)
+                .with_resistance(AttackType::Piercing, 1),
+            Gear::new("Chain shirt", GearSlot::Body)
+                .with_resistance(AttackType::Slashing, 2)
+        ],
+        Class::Mage => vec![
+            Gear::weapon("Frost staff", AttackType::Elemental(ElementalType::Frost), 5)
+                .with_bonus(StatBlock::new(0, 0, 2)),
+            Gear::new("Robe", GearSlot::Body)
+                .with_resistance(AttackType::Elemental(ElementalType::Fire), 1)
+        ],
+        Class::Rogue => vec![
+            Gear::weapon("Dagger", AttackType::Piercing, 6)
+                .with_bonus(StatBlock::new(0, 2, 0)),
+            Gear::new("Leather cap", GearSlot::Head)
+                .with_resistance(AttackType::Piercing, 1),
+            Gear::new("Soft boots", GearSlot::Feet)
+                .with_bonus(StatBlock::new(0, 1, 0))
+        ]
+    };
+
+    kit.push(Gear::new("Copper ring", GearSlot::Ring)
+                .with_bonus(StatBlock::new(1, 0, 0)));
+    kit.push(Gear::new("Ring of warding", GearSlot::Ring)
+                .with_resistance(AttackType::Elemental(ElementalType::Fire), 2)
+                .with_resistance(AttackType::Elemental(ElementalType::Lightning), 2));
+    kit
+}
@@ -0,0 +1,94 @@
+mod gear;
+mod equipment;
+mod consumable;
+
+pub use self::gear::{Gear, GearSlot, starting_kit};
+pub use self::equipment::{Equipment, EquipSlot};
+pub use self::consumable::Consumable;
+
+use utils::{Vec2, Vec3};
+use entity::{Drawable, DrawOutput};
+
+// Anything that can sit in the player's pack
+pub enum Item {
+    Gear(Gear),
+    Consumable(Consumable)
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match *self {
+            Item::Gear(ref gear) => gear.name(),
+            Item::Consumable(ref consumable) => consumable.name()
+        }
+    }
+
+    pub fn is_gear(&self) -> bool {
+        match *self {
+            Item::Gear(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn is_consumable(&self) -> bool {
+        match *self {
+            Item::Consumable(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn icon(&self) -> char {
+        match *self {
+            Item::Gear(ref gear) => {
+                match gear.slot() {
+                    GearSlot::Weapon => ')',
+                    GearSlot::Ring => '=',
+                    _ => '['
+                }
+            },
+            Item::Consumable(ref consumable) => consumable.icon()
+        }
+    }
+}
+
+// Something lying on the floor of a room
+pub struct FloorItem {
+    pos : Vec2<usize>,
+    item : Item
+}
+
+impl FloorItem {
+    pub fn new(pos : Vec2<usize>, item : Item) -> FloorItem {
+        FloorItem {
+            pos,
+            item
+        }
+    }
+
+    pub fn position(&self) -> &Vec2<usize> {
+        &self.pos
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn take(self) -> Item {
+        self.item
+    }
+}
+
+impl Drawable for FloorItem {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.pos,
+            fg : Vec3::new(0, 200, 255),
+            bg : Vec3::new(95, 95, 95),
+            icon : self.item.icon()
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "item"
+    }
+}
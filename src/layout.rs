@@ -0,0 +1,79 @@
+use utils::Vec2;
+
+// Smallest the pieces of the hud can get before we give up
+// and ask for a bigger terminal
+const MIN_VIEWPORT : Vec2<usize> = Vec2 { x : 30, y : 10 };
+const MIN_PANEL_WIDTH : usize = 22;
+const MAX_PANEL_WIDTH : usize = 36;
+const MIN_LOG_HEIGHT : usize = 3;
+const MAX_LOG_HEIGHT : usize = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rect {
+    pub origin : Vec2<usize>,
+    pub size : Vec2<usize>
+}
+
+impl Rect {
+    pub fn new(origin : Vec2<usize>, size : Vec2<usize>) -> Rect {
+        Rect {
+            origin,
+            size
+        }
+    }
+}
+
+// Splits the screen into the map viewport, the side panel with
+// player/target info and the message log under the map.
+//
+//  +----------------+-------+
+//  |                |player |
+//  |    viewport    |-------|
+//  |                |target |
+//  |----------------|       |
+//  |      log       |       |
+//  +----------------+-------+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Layout {
+    pub screen : Vec2<usize>,
+    pub viewport : Rect,
+    pub side_panel : Rect,
+    pub log : Rect
+}
+
+impl Layout {
+    pub fn new(screen : Vec2<usize>) -> Layout {
+        // Panel gets about a third of the width and the log about
+        // a quarter of the height. The map gets whatever is left
+        let panel_width = clamp(screen.x / 3, MIN_PANEL_WIDTH, MAX_PANEL_WIDTH);
+        let log_height = clamp(screen.y / 4, MIN_LOG_HEIGHT, MAX_LOG_HEIGHT);
+
+        // The +1s are the borders between the sections
+        let viewport_width = screen.x.saturating_sub(panel_width + 1).max(MIN_VIEWPORT.x);
+        let viewport_height = screen.y.saturating_sub(log_height + 1).max(MIN_VIEWPORT.y);
+
+        Layout {
+            screen,
+            viewport : Rect::new(Vec2::new(0, 0), 
+                                 Vec2::new(viewport_width, viewport_height)),
+            side_panel : Rect::new(Vec2::new(viewport_width + 1, 0), 
+                                   Vec2::new(panel_width, screen.y)),
+            log : Rect::new(Vec2::new(0, viewport_height + 1), 
+                            Vec2::new(viewport_width, log_height))
+        }
+    }
+
+    pub fn min_size() -> Vec2<usize> {
+        Vec2::new(MIN_VIEWPORT.x + MIN_PANEL_WIDTH + 1,
+                  MIN_VIEWPORT.y + MIN_LOG_HEIGHT + 1)
+    }
+
+    pub fn too_small(&self) -> bool {
+        let min = Layout::min_size();
+        self.screen.x < min.x || self.screen.y < min.y
+    }
+}
+
+fn clamp(value : usize, min : usize, max : usize) -> usize {
+    value.max(min).min(max)
+}
@@ -1,26 +1,44 @@
 extern crate chrono;
+#[macro_use]
+extern crate serde_derive;
 
 mod utils;
 mod entity;
 mod world;
+mod camera;
+mod layout;
 
 pub mod player;
+pub mod item;
 pub mod rogue_view;
+pub mod render;
 pub mod goblin;
+pub mod monster;
 pub mod log;
 pub mod input;
+pub mod spell;
+pub mod action;
 
-use utils::Vec2;
+use utils::distance;
 use entity::StatBlock;
+use entity::{Attack, StatusEffects};
 use entity::EntityMap;
 use world::World;
+use layout::Layout;
+use item::{Item, EquipSlot, Consumable};
 use input::{Input, MouseEvent, MouseButton};
+use action::Action;
+use spell::Spell;
 
 // Change at some point?
 pub use player::Player;
 pub use goblin::Goblin;
 pub use log::Log;
 
+// For tools that build dungeons outside a game, see src/bin
+pub use utils::{Vec2, seed};
+pub use world::{Dungeon, DungeonProperties, FloorStats};
+
 pub struct GameOptions {
     width : usize,
     height : usize,
@@ -43,51 +61,118 @@ impl GameOptions {
     }
 }
 
+// Longest a single rest can go on for
+const MAX_REST_TURNS : usize = 500;
+
+// Stops auto explore running away forever
+const MAX_EXPLORE_TURNS : usize = 1000;
+
+// What the keyboard and mouse are currently driving
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Mode {
+    Normal,
+    // Cursor position in the room
+    Look(Vec2<usize>),
+    // Lining up a shot on the monster with this uuid
+    Target(usize),
+    // Right click menu for the cell, with the selected entry
+    Menu(Vec2<usize>, usize)
+}
+
 pub struct Game {
     player : Player,
     world : World,
-    viewport : Vec2<usize>,
-    step : bool,
+    layout : Layout,
+    mode : Mode,
+    last_shot : Vec<Vec2<usize>>,
+    menu : Vec<Action>,
+    steps : usize,
     log : Log
 }
 
 impl Game {
     pub fn new(options : GameOptions) -> Self {
         let world = World::new();
-        let player = player::Player::new(
+        let mut player = player::Player::new(
                         options.player_name, 
                         world.starting_position()
                      );
 
+        // Start off wearing the class kit
+        for gear in item::starting_kit(options.player_class) {
+            player.wear(gear);
+        }
+        player.add_item(Item::Consumable(Consumable::HealingPotion));
+        player.add_item(Item::Consumable(Consumable::Food));
+
         Game {
             player,
             world,
-            viewport : Vec2::new(60, 20),
-            step : false,
+            layout : Layout::new(Vec2::new(options.width, options.height)),
+            mode : Mode::Normal,
+            last_shot : Vec::new(),
+            menu : Vec::new(),
+            steps : 0,
             log : Log::new(20)
         }
     }
 
     fn step(&mut self) {
-        self.world.step(&mut self.player);
+        self.world.step(&mut self.player, &mut self.log);
+
+        let damage = self.player.status_mut().tick();
+        if damage > 0 {
+            let pos = *self.player.position();
+            let result = self.player.receive_attack(&Attack::over_time(damage, pos));
+            self.log.add_message(&format!("You suffer {} damage", result.dmg_dealt));
+        }
+
+        self.player.regenerate();
+    }
+
+    // Stunned or frozen players can lose their turn.
+    // The world still moves on without them
+    fn ready(&mut self) -> bool {
+        if !self.player.status().can_act() {
+            self.log.add_message("You can't move!");
+            self.steps = 1;
+            return false;
+        }
+        true
     }
 
     pub fn handle_input(&mut self, input : &Input) {    
-        match input {
-            Input::Right => self.process_move(1, 0),
-            Input::Left => self.process_move(-1, 0),
-            Input::Up => self.process_move(0, -1),
-            Input::Down => self.process_move(0, 1),
-            Input::Key(key) => self.process_char(*key),
-            Input::Mouse{offset, position, event} => self.process_mouse(*position, event),
-            _ => {}
+        // A shot is only shown for the turn it was fired
+        self.last_shot.clear();
+
+        match self.mode {
+            Mode::Look(cursor) => self.process_look(cursor, input),
+            Mode::Target(uuid) => self.process_target(uuid, input),
+            Mode::Menu(pos, selected) => self.process_menu(pos, selected, input),
+            Mode::Normal => {
+                match input {
+                    Input::Right => self.process_move(1, 0),
+                    Input::Left => self.process_move(-1, 0),
+                    Input::Up => self.process_move(0, -1),
+                    Input::Down => self.process_move(0, 1),
+                    Input::Key(key) => self.process_char(*key),
+                    Input::Mouse{offset, position, event} => self.process_mouse(*position, event),
+                    _ => {}
+                }
+            }
         }
 
-        // Player moved/attacked so update world
-        if self.step {  // Only certain input events trigger a step
+        self.run_steps();
+    }
+
+    // Player moved/attacked so update world
+    // Only certain input events trigger a step and
+    // slow terrain can take more than one
+    fn run_steps(&mut self) {
+        for _ in 0..self.steps {
             self.step();
-            self.step = false;
         }
+        self.steps = 0;
     }
 
     pub fn world(&self) -> &World {
@@ -103,11 +188,87 @@ impl Game {
     }
 
     pub fn viewport_width(&self) -> usize {
-        self.viewport.x
+        self.layout.viewport.size.x
     }
 
     pub fn viewport_height(&self) -> usize {
-        self.viewport.y
+        self.layout.viewport.size.y
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn look_cursor(&self) -> Option<Vec2<usize>> {
+        match self.mode {
+            Mode::Look(cursor) => Some(cursor),
+            _ => None
+        }
+    }
+
+    // The cell the menu is for, which entry is selected and what's in it
+    pub fn menu(&self) -> Option<(Vec2<usize>, usize, &Vec<Action>)> {
+        match self.mode {
+            Mode::Menu(pos, selected) => Some((pos, selected, &self.menu)),
+            _ => None
+        }
+    }
+
+    // Either the shot being lined up or the one just fired
+    pub fn projectile_path(&self) -> Vec<Vec2<usize>> {
+        if let Mode::Target(uuid) = self.mode {
+            if let Some(end) = self.monster_position(uuid) {
+                let start = *self.player.position();
+                return self.world.projectile_path(start, end, self.player.range());
+            }
+        }
+
+        self.last_shot.clone()
+    }
+
+    // Changing gear takes a turn
+    pub fn equip(&mut self, index : usize) {
+        if self.ready() {
+            if let Some(name) = self.player.equip(index) {
+                self.log.add_message(&format!("You equip the {}", name));
+                self.steps = 1;
+            }
+        }
+        self.run_steps();
+    }
+
+    pub fn use_item(&mut self, index : usize) {
+        self.consume(index);
+        self.run_steps();
+    }
+
+    // Consumables in the pack along with their inventory index.
+    // The number keys use them in this order
+    pub fn consumables(&self) -> Vec<usize> {
+        self.player.inventory().iter()
+                   .enumerate()
+                   .filter(|&(_, item)| item.is_consumable())
+                   .map(|(i, _)| i)
+                   .collect()
+    }
+
+    pub fn unequip(&mut self, slot : EquipSlot) {
+        if self.ready() {
+            if let Some(name) = self.player.unequip(slot) {
+                self.log.add_message(&format!("You take off the {}", name));
+                self.steps = 1;
+            }
+        }
+        self.run_steps();
+    }
+
+    // Screen size changed so work out the hud again
+    pub fn resize(&mut self, width : usize, height : usize) {
+        self.layout = Layout::new(Vec2::new(width, height));
     }
 
     pub fn get_log_messages(&self, msg_count : usize) -> Vec<String> {
@@ -149,6 +310,16 @@ impl Game {
         None
     }
 
+    pub fn target_status(&self) -> Option<&StatusEffects> {
+        if let Some(uuid) = self.player.target() {
+            let result = self.get_entities();
+            if let Some(entities) = result {
+                return Some(entities[&uuid].status());
+            }
+        }
+        None
+    }
+
     pub fn target_base_stats(&self) -> Option<&StatBlock> {
         if let Some(uuid) = self.player.target() {
             let result = self.get_entities();
@@ -162,6 +333,10 @@ impl Game {
     /////////////////////////////////////////////////
 
     fn process_move(&mut self, x_dir : i32, y_dir : i32) {
+        if !self.ready() {
+            return;
+        }
+
         let mut lcl_x = x_dir;
         let mut lcl_y = y_dir;
 
@@ -178,24 +353,497 @@ impl Game {
                                 
         self.world.handle_player_input(&mut self.player, new_pos, &mut self.log);
 
-        self.step = true;
+        self.steps = 1;
+        if *self.player.position() == new_pos {
+            self.steps = self.world.movement_cost(new_pos);
+        }
     }
 
-    fn process_char(&self, key : char) {
-        println!("{}",key);
+    fn process_char(&mut self, key : char) {
+        match key {
+            'x' => {
+                // Start looking at whatever we have targeted
+                let mut cursor = *self.player.position();
+                if let Some(uuid) = self.player.target() {
+                    if let Some(entities) = self.world.get_entities() {
+                        if let Some(e) = entities.get(&uuid) {
+                            cursor = *e.position();
+                        }
+                    }
+                }
+                self.mode = Mode::Look(cursor);
+            },
+            'f' => {
+                let visible = self.visible_monsters();
+                if visible.is_empty() {
+                    self.log.add_message("There is nothing to shoot at");
+                    return;
+                }
+
+                // Stick with the current target if we can see it
+                let mut uuid = visible[0];
+                if let Some(target) = self.player.target() {
+                    if visible.contains(&target) {
+                        uuid = target;
+                    }
+                }
+                self.player.set_target(uuid);
+                self.mode = Mode::Target(uuid);
+            },
+            '1'..='9' => {
+                let slot = key.to_digit(10).unwrap() as usize - 1;
+                if let Some(index) = self.consumables().get(slot) {
+                    self.consume(*index);
+                }
+            },
+            'r' => self.rest(),
+            'o' => self.explore(),
+            'c' => {
+                if !self.ready() {
+                    return;
+                }
+
+                let pos = *self.player.position();
+                if self.world.close_door(pos) {
+                    self.log.add_message("You close the door");
+                    self.steps = 1;
+                }
+            },
+            _ => {}
+        }
     }
 
     fn process_mouse(&mut self, position : Vec2<usize>, event : &input::MouseEvent)  {
+        if let MouseEvent::Press(MouseButton::Right) = event {
+            self.open_menu(position);
+            return;
+        }
+
         if let MouseEvent::Press(MouseButton::Left) = event {
+            // Clicking a monster targets it, clicking anywhere else walks there
+            let mut clicked_monster = false;
             let result = self.world.get_entities();
             if let Some(entities) = result {
                 for (uuid, m) in entities {
                     if m.collision(position) {
                         self.player.set_target(*uuid);
+                        clicked_monster = true;
+                    }
+                }
+            }
+
+            if !clicked_monster {
+                self.travel(position);
+            }
+        }
+    }
+
+    // Walk along a path one turn at a time. Stops if we get hit
+    // or something new shows up
+    fn travel(&mut self, dest : Vec2<usize>) {
+        let start = *self.player.position();
+        if start == dest {
+            return;
+        }
+
+        let path = match self.world.find_path(start, dest) {
+            Some(path) => path,
+            None => {
+                self.log.add_message("You can't get there");
+                return;
+            }
+        };
+
+        let seen = self.visible_monsters();
+        for next in path {
+            if !self.travel_step(next, &seen) {
+                break;
+            }
+        }
+    }
+
+    // Walks the floor on its own until something interesting happens
+    fn explore(&mut self) {
+        if !self.visible_monsters().is_empty() {
+            self.log.add_message("You can't explore with monsters in sight");
+            return;
+        }
+
+        let mut items = self.visible_items();
+        for _ in 0..MAX_EXPLORE_TURNS {
+            let pos = *self.player.position();
+            let next = match self.world.explore_step(pos) {
+                Some(next) => next,
+                None => {
+                    if self.world.fully_visited() {
+                        self.log.add_message("You have explored the whole floor");
+                    }
+                    else {
+                        self.log.add_message("There is nowhere left you can reach");
+                    }
+                    return;
+                }
+            };
+
+            if !self.travel_step(next, &Vec::new()) {
+                return;
+            }
+
+            let in_sight = self.visible_items();
+            if in_sight.iter().any(|pos| !items.contains(pos)) {
+                self.log.add_message("You stop, there is something on the floor");
+                return;
+            }
+            items = in_sight;
+        }
+    }
+
+    // One step towards next, a turn passes. False if we didn't get
+    // anywhere, got hit or a monster that isn't in seen showed up
+    fn travel_step(&mut self, next : Vec2<usize>, seen : &Vec<usize>) -> bool {
+        let pos = *self.player.position();
+        let x_dir = next.x as i32 - pos.x as i32;
+        let y_dir = next.y as i32 - pos.y as i32;
+
+        // Same check as resting, healing on the same turn can't hide a hit
+        self.player.take_recent_damage();
+        self.process_move(x_dir, y_dir);
+        self.run_steps();
+        let mut hit = self.player.take_recent_damage() > 0;
+
+        // Walking into a door just opens it
+        let blocked = match self.world.get_entities() {
+            Some(entities) => entities.values().any(|m| m.collision(next)),
+            None => false
+        };
+        if !blocked && *self.player.position() == pos && !hit {
+            self.process_move(x_dir, y_dir);
+            self.run_steps();
+            hit = self.player.take_recent_damage() > 0;
+        }
+
+        // Going through an exit puts us somewhere else entirely
+        // so only check that we moved at all
+        if *self.player.position() == pos {
+            return false;
+        }
+        if hit {
+            self.log.add_message("You stop, something hit you");
+            return false;
+        }
+        if self.visible_monsters().iter().any(|uuid| !seen.contains(uuid)) {
+            self.log.add_message("You stop, a monster comes into view");
+            return false;
+        }
+
+        true
+    }
+
+    // Where the items the player can see are
+    fn visible_items(&self) -> Vec<Vec2<usize>> {
+        let width = self.world.room_size().x;
+        let visible = self.world.field_of_view(*self.player.position());
+
+        match self.world.get_items() {
+            Some(items) => items.iter()
+                                .map(|i| *i.position())
+                                .filter(|pos| visible[pos.x + pos.y * width])
+                                .collect(),
+            None => Vec::new()
+        }
+    }
+
+    // Looking around never takes a turn
+    fn process_look(&mut self, cursor : Vec2<usize>, input : &Input) {
+        let size = self.world.room_size();
+        let mut cursor = cursor;
+
+        match input {
+            Input::Right => if cursor.x + 1 < size.x { cursor.x += 1 },
+            Input::Left => if cursor.x > 0 { cursor.x -= 1 },
+            Input::Up => if cursor.y > 0 { cursor.y -= 1 },
+            Input::Down => if cursor.y + 1 < size.y { cursor.y += 1 },
+            Input::Key('x') | Input::Escape => {
+                self.mode = Mode::Normal;
+                return;
+            },
+            Input::Mouse{position, event : MouseEvent::Press(_), ..} |
+            Input::Mouse{position, event : MouseEvent::Hold(_), ..} => cursor = *position,
+            _ => {}
+        }
+
+        self.mode = Mode::Look(cursor);
+    }
+
+    fn process_target(&mut self, uuid : usize, input : &Input) {
+        match input {
+            // Cycle to the next monster in sight, closest first
+            Input::Tab => {
+                let visible = self.visible_monsters();
+                if visible.is_empty() {
+                    self.mode = Mode::Normal;
+                    return;
+                }
+
+                let next = match visible.iter().position(|id| *id == uuid) {
+                    Some(i) => visible[(i + 1) % visible.len()],
+                    None => visible[0]
+                };
+                self.player.set_target(next);
+                self.mode = Mode::Target(next);
+            },
+            Input::Enter | Input::Key('f') => {
+                self.mode = Mode::Normal;
+                if let Some(end) = self.monster_position(uuid) {
+                    if self.ready() {
+                        self.last_shot = self.world.fire_projectile(&mut self.player, end, None, &mut self.log);
+                        self.steps = 1;
                     }
                 }
+            },
+            Input::Escape => self.mode = Mode::Normal,
+            _ => {}
+        }
+    }
+
+    fn open_menu(&mut self, pos : Vec2<usize>) {
+        self.menu = self.context_actions(pos);
+        self.mode = Mode::Menu(pos, 0);
+    }
+
+    // What can be done depends on what's in the cell
+    fn context_actions(&self, pos : Vec2<usize>) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let player_pos = *self.player.position();
+        let visible = self.world.field_of_view(player_pos);
+        let in_sight = visible[pos.x + pos.y * self.world.room_size().x];
+
+        let monster = self.visible_monsters().into_iter()
+                          .find(|uuid| self.monster_position(*uuid) == Some(pos));
+        if let Some(uuid) = monster {
+            if distance(player_pos, pos) == 1 {
+                actions.push(Action::Attack(uuid));
+            }
+            actions.push(Action::Target(uuid));
+            // Only offer it when the bolt would actually get there
+            let spell = Spell::Firebolt;
+            let path = self.world.projectile_path(player_pos, pos, self.player.range());
+            if self.player.current_stats().mana >= spell.mana_cost() && path.last() == Some(&pos) {
+                actions.push(Action::CastSpell(spell));
+            }
+            actions.push(Action::Examine);
+            return actions;
+        }
+
+        if let Some(tile) = self.world.tile(pos) {
+            if let world::TileType::Door{open : false} = tile.id {
+                actions.push(Action::OpenDoor);
+            }
+        }
+
+        let corpse = match self.world.get_corpses() {
+            Some(corpses) => corpses.values().any(|c| *c.position() == pos && !c.looted()),
+            None => false
+        };
+        if corpse && in_sight {
+            actions.push(Action::LootCorpse);
+        }
+
+        if pos != player_pos && self.world.find_path(player_pos, pos).is_some() {
+            actions.push(Action::Travel);
+        }
+        actions.push(Action::Examine);
+
+        actions
+    }
+
+    // Picking from the menu doesn't take a turn, whatever it does might
+    fn process_menu(&mut self, pos : Vec2<usize>, selected : usize, input : &Input) {
+        let count = self.menu.len();
+        match input {
+            Input::Up => self.mode = Mode::Menu(pos, (selected + count - 1) % count),
+            Input::Down => self.mode = Mode::Menu(pos, (selected + 1) % count),
+            Input::Enter => self.select_action(pos, selected),
+            Input::Key(key @ '1'..='9') => {
+                let index = key.to_digit(10).unwrap() as usize - 1;
+                if index < count {
+                    self.select_action(pos, index);
+                }
+            },
+            Input::Mouse{position, event : MouseEvent::Press(MouseButton::Right), ..} => 
+                self.open_menu(*position),
+            Input::Escape | Input::Mouse{event : MouseEvent::Press(_), ..} => 
+                self.mode = Mode::Normal,
+            _ => {}
+        }
+    }
+
+    fn select_action(&mut self, pos : Vec2<usize>, index : usize) {
+        let action = self.menu[index];
+        self.mode = Mode::Normal;
+        self.menu.clear();
+
+        match action {
+            Action::Attack(_) => {
+                let player_pos = *self.player.position();
+                self.process_move(pos.x as i32 - player_pos.x as i32, 
+                                  pos.y as i32 - player_pos.y as i32);
+            },
+            Action::Target(uuid) => self.player.set_target(uuid),
+            Action::CastSpell(spell) => self.cast(spell, pos),
+            Action::Examine => self.mode = Mode::Look(pos),
+            Action::Travel => self.travel(pos),
+            Action::LootCorpse => {
+                self.travel(pos);
+                if *self.player.position() != pos || !self.ready() {
+                    return;
+                }
+                if self.world.loot_corpse(pos, &mut self.player, &mut self.log) {
+                    self.steps = 1;
+                }
+            },
+            Action::OpenDoor => self.open_door(pos)
+        }
+    }
+
+    // Walk up to the door then push on it
+    fn open_door(&mut self, pos : Vec2<usize>) {
+        let start = *self.player.position();
+        let mut path = match self.world.find_path(start, pos) {
+            Some(path) => path,
+            None => {
+                self.log.add_message("You can't get there");
+                return;
+            }
+        };
+        path.pop();
+
+        let seen = self.visible_monsters();
+        for next in path {
+            if !self.travel_step(next, &seen) {
+                return;
+            }
+        }
+
+        let player_pos = *self.player.position();
+        if distance(player_pos, pos) == 1 {
+            self.process_move(pos.x as i32 - player_pos.x as i32, 
+                              pos.y as i32 - player_pos.y as i32);
+        }
+    }
+
+    fn cast(&mut self, spell : Spell, end : Vec2<usize>) {
+        if !self.ready() {
+            return;
+        }
+
+        if !self.player.spend_mana(spell.mana_cost()) {
+            self.log.add_message(&format!("You don't have enough mana to cast {}", spell.name()));
+            return;
+        }
+
+        self.log.add_message(&format!("You cast {}", spell.name()));
+        self.last_shot = self.world.fire_projectile(&mut self.player, end, Some(spell), &mut self.log);
+        self.steps = 1;
+    }
+
+    fn monster_position(&self, uuid : usize) -> Option<Vec2<usize>> {
+        if let Some(entities) = self.world.get_entities() {
+            if let Some(e) = entities.get(&uuid) {
+                return Some(*e.position());
+            }
+        }
+        None
+    }
+
+    // Monsters the player can see, sorted by distance
+    fn visible_monsters(&self) -> Vec<usize> {
+        let pos = *self.player.position();
+        let width = self.world.room_size().x;
+        let visible = self.world.field_of_view(pos);
+
+        let mut monsters = Vec::new();
+        if let Some(entities) = self.world.get_entities() {
+            for (uuid, e) in entities {
+                let e_pos = *e.position();
+                if e.alive() && visible[e_pos.x + e_pos.y * width] {
+                    let dx = e_pos.x as i32 - pos.x as i32;
+                    let dy = e_pos.y as i32 - pos.y as i32;
+                    monsters.push((dx * dx + dy * dy, *uuid));
+                }
             }
         }
+        monsters.sort();
+
+        monsters.into_iter().map(|(_, uuid)| uuid).collect()
     }
 
-}
\ No newline at end of file
+    // Using something takes a turn
+    fn consume(&mut self, index : usize) {
+        if !self.ready() {
+            return;
+        }
+
+        let consumable = match self.player.take_consumable(index) {
+            Some(consumable) => consumable,
+            None => return
+        };
+
+        let msg = match consumable {
+            Consumable::HealingPotion => {
+                let healed = self.player.heal(10);
+                format!("You drink the healing potion and recover {} health", healed)
+            },
+            Consumable::ManaPotion => {
+                let restored = self.player.restore_mana(10);
+                format!("You drink the mana potion and recover {} mana", restored)
+            },
+            Consumable::TeleportScroll => {
+                if self.world.teleport(&mut self.player) {
+                    String::from("You read the scroll and the room shifts around you")
+                }
+                else {
+                    String::from("You read the scroll but nothing happens")
+                }
+            },
+            Consumable::MappingScroll => {
+                self.world.map_floor();
+                let traps = self.world.reveal_traps();
+                format!("You read the scroll and the whole floor comes to mind, {} hidden traps included", traps)
+            },
+            Consumable::Food => {
+                let healed = self.player.heal(3);
+                format!("You eat the ration and recover {} health", healed)
+            }
+        };
+        self.log.add_message(&msg);
+        self.steps = 1;
+    }
+
+    // Keep passing turns until healed, stopping as soon as
+    // something shows up or hurts us
+    fn rest(&mut self) {
+        if !self.visible_monsters().is_empty() {
+            self.log.add_message("You can't rest with monsters in sight");
+            return;
+        }
+
+        let mut turns = 0;
+        self.player.take_recent_damage();
+        while turns < MAX_REST_TURNS && !self.player.rested() {
+            self.step();
+            turns += 1;
+
+            if self.player.take_recent_damage() > 0 {
+                self.log.add_message("Your rest is interrupted");
+                break;
+            }
+            if !self.visible_monsters().is_empty() {
+                self.log.add_message("A monster comes into view");
+                break;
+            }
+        }
+
+        self.log.add_message(&format!("You rest for {} turns", turns));
+    }
+}
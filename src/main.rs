@@ -99,9 +99,71 @@ fn new_game(siv: &mut Cursive, name : String, class : player::Class) {
                     })
             );
             Some(EventResult::Consumed(None))
-        });
+        })
+        .on_pre_event('e', show_equipment)
+        .on_pre_event('i', show_inventory);
       
     siv.add_fullscreen_layer(
         bv
     );
-}
\ No newline at end of file
+}
+
+fn show_equipment(siv: &mut Cursive) {
+    let (equipped, pack) = siv.call_on_id("rogue", | view: &mut rogue_view::RogueView| {
+        (view.equipped(), view.pack_gear())
+    }).unwrap();
+
+    // Picking a worn slot takes it off, picking pack gear puts it on
+    let mut worn = SelectView::<usize>::new();
+    for (i, line) in equipped.into_iter().enumerate() {
+        worn.add_item(line, i);
+    }
+    worn.set_on_submit(|s, slot| {
+        s.call_on_id("rogue", | view: &mut rogue_view::RogueView| view.unequip(*slot));
+        s.pop_layer();
+    });
+
+    let mut carried = SelectView::<usize>::new();
+    for (name, index) in pack {
+        carried.add_item(name, index);
+    }
+    carried.set_on_submit(|s, index| {
+        s.call_on_id("rogue", | view: &mut rogue_view::RogueView| view.equip(*index));
+        s.pop_layer();
+    });
+
+    siv.add_layer(
+        Dialog::new()
+            .title("Equipment")
+            .content(
+                LinearLayout::vertical()
+                    .child(TextView::new("Worn:"))
+                    .child(worn)
+                    .child(TextView::new("Pack:"))
+                    .child(carried)
+            )
+            .dismiss_button("Close")
+    );
+}
+
+fn show_inventory(siv: &mut Cursive) {
+    let pack = siv.call_on_id("rogue", | view: &mut rogue_view::RogueView| {
+        view.pack_consumables()
+    }).unwrap();
+
+    let mut items = SelectView::<usize>::new();
+    for (name, index) in pack {
+        items.add_item(name, index);
+    }
+    items.set_on_submit(|s, index| {
+        s.call_on_id("rogue", | view: &mut rogue_view::RogueView| view.use_item(*index));
+        s.pop_layer();
+    });
+
+    siv.add_layer(
+        Dialog::new()
+            .title("Inventory")
+            .content(items)
+            .dismiss_button("Close")
+    );
+}
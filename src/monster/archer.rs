@@ -0,0 +1,112 @@
+use utils::{Vec2, Vec3};
+use world::Tile;
+use entity::{Attackable, Attack, AttackType, CombatResult, 
+             StatBlock, Drawable, DrawOutput, StatusEffects};
+use player::Player;
+use super::{MonsterCore, distance, step_towards, step_away, clear_shot};
+
+const REGEN_RATE : i32 = 10;
+
+// How far the arrows go and how close it lets you get
+const RANGE : usize = 6;
+const COMFORT : usize = 3;
+
+// Hangs back and shoots whenever it has a clear line
+pub struct Archer {
+    core : MonsterCore
+}
+
+impl Archer {
+    pub fn new(pos : Vec2<usize>) -> Archer {
+        Archer {
+            core : MonsterCore::new(pos, 2, 4, 1)
+        }
+    }
+}
+
+impl Attackable for Archer {
+    fn update(&mut self, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Option<Attack> {
+        let p_pos = *player.position();
+        let dist = distance(self.core.pos, p_pos);
+
+        // Backing off comes before shooting, it can't shoot well up close
+        if dist < COMFORT {
+            let next = step_away(self.core.pos, p_pos, player, tiles, room_size);
+            if next != self.core.pos {
+                self.core.pos = next;
+                return None;
+            }
+        }
+
+        if dist <= RANGE && clear_shot(self.core.pos, p_pos, tiles, room_size) {
+            let damage = self.core.status.scale_damage(self.core.curr_stats.dexterity / 2);
+            return Some(Attack::new(AttackType::Piercing, damage, p_pos));
+        }
+
+        self.core.pos = step_towards(self.core.pos, p_pos, player, tiles, room_size);
+        None
+    }
+
+    fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
+        self.core.receive_attack(attack, "an Archer")
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        self.core.position()
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.core.set_position(pos);
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.core.collision(other)
+    }
+
+    fn alive(&self) -> bool {
+        self.core.alive()
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        self.core.base_stats()
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        self.core.current_stats()
+    }
+
+    fn name(&self) -> &str {
+        "an Archer"
+    }
+
+    fn status(&self) -> &StatusEffects {
+        self.core.status()
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        self.core.status_mut()
+    }
+
+    fn regenerate(&mut self) {
+        self.core.regenerate(REGEN_RATE, 0);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.core.heal(amount);
+    }
+}
+
+impl Drawable for Archer {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.core.pos,
+            fg : Vec3::new(200, 170, 60),
+            bg : Vec3::new(95,95,95),
+            icon : 'a'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "archer"
+    }
+}
@@ -0,0 +1,109 @@
+extern crate rand;
+
+use self::rand::prelude::*;
+use utils::rng;
+use utils::{Vec2, Vec3};
+use world::Tile;
+use entity::{Attackable, Attack, AttackType, CombatResult, 
+             StatBlock, Drawable, DrawOutput, StatusEffects};
+use player::Player;
+use super::{MonsterCore, adjacent, step_towards, random_step};
+
+const REGEN_RATE : i32 = 10;
+
+// Out of 10, how often it flutters off somewhere random
+const ERRATIC_CHANCE : u32 = 5;
+
+// Weak but hard to pin down, it only heads for you half the time
+pub struct Bat {
+    core : MonsterCore
+}
+
+impl Bat {
+    pub fn new(pos : Vec2<usize>) -> Bat {
+        Bat {
+            core : MonsterCore::new(pos, 1, 5, 0)
+        }
+    }
+}
+
+impl Attackable for Bat {
+    fn update(&mut self, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Option<Attack> {
+        let p_pos = *player.position();
+        if rng().gen_range(0, 10) < ERRATIC_CHANCE {
+            self.core.pos = random_step(self.core.pos, player, tiles, room_size);
+            return None;
+        }
+
+        if adjacent(self.core.pos, p_pos) {
+            let damage = self.core.status.scale_damage(self.core.curr_stats.strength);
+            return Some(Attack::new(AttackType::Piercing, damage, p_pos));
+        }
+
+        self.core.pos = step_towards(self.core.pos, p_pos, player, tiles, room_size);
+        None
+    }
+
+    fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
+        self.core.receive_attack(attack, "a Bat")
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        self.core.position()
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.core.set_position(pos);
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.core.collision(other)
+    }
+
+    fn alive(&self) -> bool {
+        self.core.alive()
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        self.core.base_stats()
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        self.core.current_stats()
+    }
+
+    fn name(&self) -> &str {
+        "a Bat"
+    }
+
+    fn status(&self) -> &StatusEffects {
+        self.core.status()
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        self.core.status_mut()
+    }
+
+    fn regenerate(&mut self) {
+        self.core.regenerate(REGEN_RATE, 0);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.core.heal(amount);
+    }
+}
+
+impl Drawable for Bat {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.core.pos,
+            fg : Vec3::new(140, 110, 90),
+            bg : Vec3::new(95,95,95),
+            icon : 'b'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "bat"
+    }
+}
@@ -0,0 +1,185 @@
+extern crate rand;
+
+mod archer;
+mod shaman;
+mod slime;
+mod troll;
+mod bat;
+
+pub use self::archer::Archer;
+pub use self::shaman::Shaman;
+pub use self::slime::Slime;
+pub use self::troll::Troll;
+pub use self::bat::Bat;
+
+use self::rand::prelude::*;
+use utils::{Vec2, line, distance, rng};
+use world::Tile;
+use entity::{Attack, CombatResult, StatBlock, StatusEffects, Regeneration};
+use player::Player;
+
+// What every species here has in common. Each one keeps a core and
+// hands the Attackable bookkeeping off to it
+struct MonsterCore {
+    pos : Vec2<usize>,
+    base_stats : StatBlock,
+    curr_stats : StatBlock,
+    status : StatusEffects,
+    regen : Regeneration,
+    alive : bool
+}
+
+impl MonsterCore {
+    fn new(pos : Vec2<usize>, strength : i32, dexterity : i32, intelligence : i32) -> MonsterCore {
+        MonsterCore {
+            pos,
+            base_stats : StatBlock::new(strength, dexterity, intelligence),
+            curr_stats : StatBlock::new(strength, dexterity, intelligence),
+            status : StatusEffects::new(),
+            regen : Regeneration::new(),
+            alive : true
+        }
+    }
+
+    fn receive_attack(&mut self, attack : &Attack, name : &str) -> CombatResult {
+        self.curr_stats.health -= attack.damage;
+        if self.curr_stats.health <= 0 {
+            self.alive = false;
+        }
+
+        if let Some(effect) = attack.status {
+            self.status.apply(effect);
+        }
+
+        CombatResult {
+            dmg_dealt : attack.damage,
+            target_alive : self.alive,
+            target_name : String::from(name)
+        }
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        &self.pos
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.pos = pos;
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.alive && other == self.pos
+    }
+
+    fn alive(&self) -> bool {
+        self.alive
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        &self.base_stats
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        &self.curr_stats
+    }
+
+    fn status(&self) -> &StatusEffects {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        &mut self.status
+    }
+
+    fn regenerate(&mut self, health_rate : i32, mana_rate : i32) {
+        self.regen.tick(&mut self.curr_stats, &self.base_stats, health_rate, mana_rate);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.curr_stats.health = (self.curr_stats.health + amount).min(self.base_stats.health);
+    }
+}
+
+// Movement and sight helpers shared by everything that isn't a goblin.
+// None of these ever move onto the player, they attack instead
+
+fn adjacent(a : Vec2<usize>, b : Vec2<usize>) -> bool {
+    distance(a, b) == 1
+}
+
+fn neighbors(pos : Vec2<usize>, room_size : Vec2<usize>) -> Vec<Vec2<usize>> {
+    let mut result = Vec::new();
+    if pos.x > 0 {
+        result.push(Vec2::new(pos.x - 1, pos.y));
+    }
+    if pos.y > 0 {
+        result.push(Vec2::new(pos.x, pos.y - 1));
+    }
+    if pos.x + 1 < room_size.x {
+        result.push(Vec2::new(pos.x + 1, pos.y));
+    }
+    if pos.y + 1 < room_size.y {
+        result.push(Vec2::new(pos.x, pos.y + 1));
+    }
+
+    result
+}
+
+fn open(pos : Vec2<usize>, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> bool {
+    tiles[pos.x + pos.y * room_size.x].walkable() && !player.collision(pos)
+}
+
+// Whichever open neighbor gets closest to the target
+fn step_towards(pos : Vec2<usize>, target : Vec2<usize>, player : &Player, 
+                tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Vec2<usize> 
+{
+    let mut best = pos;
+    for next in neighbors(pos, room_size) {
+        if open(next, player, tiles, room_size) && distance(next, target) < distance(best, target) {
+            best = next;
+        }
+    }
+
+    best
+}
+
+fn step_away(pos : Vec2<usize>, from : Vec2<usize>, player : &Player, 
+             tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Vec2<usize> 
+{
+    let mut best = pos;
+    for next in neighbors(pos, room_size) {
+        if open(next, player, tiles, room_size) && distance(next, from) > distance(best, from) {
+            best = next;
+        }
+    }
+
+    best
+}
+
+fn random_step(pos : Vec2<usize>, player : &Player, 
+               tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Vec2<usize> 
+{
+    let options : Vec<Vec2<usize>> = neighbors(pos, room_size).into_iter()
+                                        .filter(|next| open(*next, player, tiles, room_size))
+                                        .collect();
+    if options.is_empty() {
+        return pos;
+    }
+
+    options[rng().gen_range(0, options.len())]
+}
+
+// Nothing solid or standing in the way between the two
+fn clear_shot(from : Vec2<usize>, to : Vec2<usize>, 
+              tiles : &Vec<Tile>, room_size : Vec2<usize>) -> bool 
+{
+    let points = line(from, to);
+    if points.len() <= 2 {
+        return true;
+    }
+
+    points[1..points.len() - 1].iter().all(|p| {
+        let tile = &tiles[p.x + p.y * room_size.x];
+        let properties = tile.id.properties();
+        properties.passable && !properties.opaque && !tile.occupied
+    })
+}
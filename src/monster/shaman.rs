@@ -0,0 +1,119 @@
+use utils::{Vec2, Vec3};
+use world::Tile;
+use entity::{Attackable, Attack, AttackType, CombatResult, 
+             StatBlock, Drawable, DrawOutput, StatusEffects};
+use player::Player;
+use super::{MonsterCore, distance, adjacent, step_towards, step_away};
+
+const REGEN_RATE : i32 = 10;
+const MANA_RATE : i32 = 25;
+
+const HEAL_COST : i32 = 4;
+const HEAL_AMOUNT : i32 = 3;
+const HEAL_RANGE : usize = 4;
+
+// Stays a few tiles back and patches up whatever is fighting you
+pub struct Shaman {
+    core : MonsterCore
+}
+
+impl Shaman {
+    pub fn new(pos : Vec2<usize>) -> Shaman {
+        Shaman {
+            core : MonsterCore::new(pos, 2, 1, 4)
+        }
+    }
+}
+
+impl Attackable for Shaman {
+    fn update(&mut self, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Option<Attack> {
+        let p_pos = *player.position();
+        if adjacent(self.core.pos, p_pos) {
+            let damage = self.core.status.scale_damage(self.core.curr_stats.strength / 2);
+            return Some(Attack::new(AttackType::Piercing, damage, p_pos));
+        }
+
+        let dist = distance(self.core.pos, p_pos);
+        if dist < 3 {
+            self.core.pos = step_away(self.core.pos, p_pos, player, tiles, room_size);
+        }
+        else if dist > HEAL_RANGE {
+            self.core.pos = step_towards(self.core.pos, p_pos, player, tiles, room_size);
+        }
+
+        None
+    }
+
+    fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
+        self.core.receive_attack(attack, "a Shaman")
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        self.core.position()
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.core.set_position(pos);
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.core.collision(other)
+    }
+
+    fn alive(&self) -> bool {
+        self.core.alive()
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        self.core.base_stats()
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        self.core.current_stats()
+    }
+
+    fn name(&self) -> &str {
+        "a Shaman"
+    }
+
+    fn status(&self) -> &StatusEffects {
+        self.core.status()
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        self.core.status_mut()
+    }
+
+    fn regenerate(&mut self) {
+        self.core.regenerate(REGEN_RATE, MANA_RATE);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.core.heal(amount);
+    }
+
+    // Only when there's mana for it
+    fn heal_allies(&mut self) -> Option<(i32, usize)> {
+        if self.core.curr_stats.mana < HEAL_COST {
+            return None;
+        }
+
+        self.core.curr_stats.mana -= HEAL_COST;
+        Some((HEAL_AMOUNT, HEAL_RANGE))
+    }
+}
+
+impl Drawable for Shaman {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.core.pos,
+            fg : Vec3::new(150, 90, 220),
+            bg : Vec3::new(95,95,95),
+            icon : 's'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "shaman"
+    }
+}
@@ -0,0 +1,132 @@
+use utils::{Vec2, Vec3};
+use world::Tile;
+use entity::{Attackable, Attack, AttackType, CombatResult, Entity,
+             StatBlock, Drawable, DrawOutput, StatusEffects};
+use player::Player;
+use super::{MonsterCore, adjacent, step_towards};
+
+const REGEN_RATE : i32 = 5;
+
+// Halves get too small to split past this
+const MIN_SPLIT_HEALTH : i32 = 2;
+
+// Slow and squishy, but every hit that doesn't kill it 
+// leaves another one behind
+pub struct Slime {
+    core : MonsterCore,
+    // Slimes only move every other turn
+    resting : bool,
+    splitting : bool
+}
+
+impl Slime {
+    pub fn new(pos : Vec2<usize>) -> Slime {
+        Slime {
+            core : MonsterCore::new(pos, 12, 1, 0),
+            resting : false,
+            splitting : false
+        }
+    }
+}
+
+impl Attackable for Slime {
+    fn update(&mut self, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Option<Attack> {
+        let p_pos = *player.position();
+        if adjacent(self.core.pos, p_pos) {
+            let damage = self.core.status.scale_damage(self.core.curr_stats.strength / 4);
+            return Some(Attack::new(AttackType::Piercing, damage, p_pos));
+        }
+
+        self.resting = !self.resting;
+        if !self.resting {
+            self.core.pos = step_towards(self.core.pos, p_pos, player, tiles, room_size);
+        }
+
+        None
+    }
+
+    fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
+        let result = self.core.receive_attack(attack, "a Slime");
+        if self.core.alive && self.core.curr_stats.health >= MIN_SPLIT_HEALTH {
+            self.splitting = true;
+        }
+        result
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        self.core.position()
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.core.set_position(pos);
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.core.collision(other)
+    }
+
+    fn alive(&self) -> bool {
+        self.core.alive()
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        self.core.base_stats()
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        self.core.current_stats()
+    }
+
+    fn name(&self) -> &str {
+        "a Slime"
+    }
+
+    fn status(&self) -> &StatusEffects {
+        self.core.status()
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        self.core.status_mut()
+    }
+
+    fn regenerate(&mut self) {
+        self.core.regenerate(REGEN_RATE, 0);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.core.heal(amount);
+    }
+
+    // Health is shared between the two halves
+    fn split(&mut self, pos : Vec2<usize>) -> Option<Box<Entity>> {
+        if !self.splitting || !self.core.alive {
+            return None;
+        }
+        self.splitting = false;
+
+        let half = self.core.curr_stats.health / 2;
+        self.core.curr_stats.health -= half;
+        self.core.base_stats.health = self.core.curr_stats.health;
+
+        let mut child = Slime::new(pos);
+        child.core.curr_stats.health = half;
+        child.core.base_stats.health = half;
+
+        Some(Box::new(child))
+    }
+}
+
+impl Drawable for Slime {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.core.pos,
+            fg : Vec3::new(90, 220, 90),
+            bg : Vec3::new(95,95,95),
+            icon : 'j'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "slime"
+    }
+}
@@ -0,0 +1,102 @@
+use utils::{Vec2, Vec3};
+use world::Tile;
+use entity::{Attackable, Attack, AttackType, CombatResult, StatusKind,
+             StatBlock, Drawable, DrawOutput, StatusEffects};
+use player::Player;
+use super::{MonsterCore, adjacent, step_towards};
+
+// A whole point of health every turn
+const REGEN_RATE : i32 = 100;
+
+// Big, hits hard and heals back almost as fast as you hurt it.
+// Fire is the only thing that stops it knitting back together
+pub struct Troll {
+    core : MonsterCore
+}
+
+impl Troll {
+    pub fn new(pos : Vec2<usize>) -> Troll {
+        Troll {
+            core : MonsterCore::new(pos, 15, 1, 1)
+        }
+    }
+}
+
+impl Attackable for Troll {
+    fn update(&mut self, player : &Player, tiles : &Vec<Tile>, room_size : Vec2<usize>) -> Option<Attack> {
+        let p_pos = *player.position();
+        if adjacent(self.core.pos, p_pos) {
+            let damage = self.core.status.scale_damage(self.core.curr_stats.strength / 4);
+            return Some(Attack::new(AttackType::Slashing, damage, p_pos));
+        }
+
+        self.core.pos = step_towards(self.core.pos, p_pos, player, tiles, room_size);
+        None
+    }
+
+    fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
+        self.core.receive_attack(attack, "a Troll")
+    }
+
+    fn position(&self) -> &Vec2<usize> {
+        self.core.position()
+    }
+
+    fn set_position(&mut self, pos : Vec2<usize>) {
+        self.core.set_position(pos);
+    }
+
+    fn collision(&self, other : Vec2<usize>) -> bool {
+        self.core.collision(other)
+    }
+
+    fn alive(&self) -> bool {
+        self.core.alive()
+    }
+
+    fn base_stats(&self) -> &StatBlock {
+        self.core.base_stats()
+    }
+
+    fn current_stats(&self) -> &StatBlock {
+        self.core.current_stats()
+    }
+
+    fn name(&self) -> &str {
+        "a Troll"
+    }
+
+    fn status(&self) -> &StatusEffects {
+        self.core.status()
+    }
+
+    fn status_mut(&mut self) -> &mut StatusEffects {
+        self.core.status_mut()
+    }
+
+    fn regenerate(&mut self) {
+        if self.core.status.has(StatusKind::Burning) {
+            return;
+        }
+        self.core.regenerate(REGEN_RATE, 0);
+    }
+
+    fn heal(&mut self, amount : i32) {
+        self.core.heal(amount);
+    }
+}
+
+impl Drawable for Troll {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.core.pos,
+            fg : Vec3::new(120, 160, 90),
+            bg : Vec3::new(95,95,95),
+            icon : 't'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "troll"
+    }
+}
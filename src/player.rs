@@ -1,6 +1,11 @@
 use std::fmt;
-use utils::Vec2;
-use entity::{StatBlock, Facing, Attack, CombatResult, AttackType};
+use utils::{Vec2, Vec3};
+use entity::{StatBlock, Facing, Attack, CombatResult, AttackType, Drawable, DrawOutput,
+             StatusEffects, Regeneration};
+use item::{Item, Gear, Consumable, Equipment, EquipSlot};
+
+// How many tiles a shot can travel
+const RANGE : usize = 8;
 
 #[derive(Debug, Copy, Clone)]
 pub enum Class {
@@ -15,7 +20,13 @@ pub struct Player {
     base_stats : StatBlock,
     curr_stats : StatBlock,
     facing : Facing,
-    target : Option<usize>
+    status : StatusEffects,
+    regen : Regeneration,
+    equipment : Equipment,
+    inventory : Vec<Item>,
+    target : Option<usize>,
+    // Everything taken since take_recent_damage was last called
+    recent_damage : i32
 }
 
 impl Player {
@@ -26,7 +37,12 @@ impl Player {
             base_stats : StatBlock::new(10, 10, 10),
             curr_stats : StatBlock::new(10, 10, 10),
             facing : Facing::East,
-            target : None
+            status : StatusEffects::new(),
+            regen : Regeneration::new(),
+            equipment : Equipment::new(),
+            inventory : Vec::new(),
+            target : None,
+            recent_damage : 0
         }
     }
 
@@ -60,20 +76,48 @@ impl Player {
 
     pub fn send_attack(&self) -> Attack {
         let atk_pos = self.facing.position(self.pos);
-        let damage = (self.curr_stats.strength * 3)/2;
+
+        // Bare handed if there's nothing equipped
+        let (attack_type, damage) = match self.equipment.weapon() {
+            Some(weapon) => (weapon.attack_type(), weapon.damage() + self.curr_stats.strength),
+            None => (AttackType::Piercing, (self.curr_stats.strength * 3)/2)
+        };
+        Attack::new(attack_type, self.status.scale_damage(damage), atk_pos)
+    }
+
+    // Bows and thrown things go off dexterity instead
+    pub fn send_ranged_attack(&self, atk_pos : Vec2<usize>) -> Attack {
+        let damage = self.status.scale_damage(self.curr_stats.dexterity);
         Attack::new(AttackType::Piercing, damage, atk_pos)
     }
 
+    pub fn range(&self) -> usize {
+        RANGE
+    }
+
     pub fn receive_attack(&mut self, attack : &Attack) -> CombatResult {
-        self.curr_stats.health -= attack.damage;
+        let resisted = self.equipment.resistance(attack.attack_type);
+        let damage = (attack.damage - resisted).max(0);
+        self.curr_stats.health -= damage;
+        self.recent_damage += damage;
+        if let Some(effect) = attack.status {
+            self.status.apply(effect);
+        }
         
         CombatResult {
-            dmg_dealt : attack.damage,
+            dmg_dealt : damage,
             target_alive : self.alive(),
             target_name : self.name.clone()
         }
     }
 
+    // Regeneration can hide a hit if you only look at health
+    pub fn take_recent_damage(&mut self) -> i32 {
+        let damage = self.recent_damage;
+        self.recent_damage = 0;
+        damage
+    }
+
     pub fn position(&self) -> &Vec2<usize> {
         &self.pos
     }
@@ -98,6 +142,118 @@ impl Player {
         &self.name
     }
 
+    pub fn status(&self) -> &StatusEffects {
+        &self.status
+    }
+
+    pub fn status_mut(&mut self) -> &mut StatusEffects {
+        &mut self.status
+    }
+
+    pub fn equipment(&self) -> &Equipment {
+        &self.equipment
+    }
+
+    pub fn inventory(&self) -> &Vec<Item> {
+        &self.inventory
+    }
+
+    pub fn add_item(&mut self, item : Item) {
+        self.inventory.push(item);
+    }
+
+    // Pulls a consumable out of the pack so it can be used
+    pub fn take_consumable(&mut self, index : usize) -> Option<Consumable> {
+        if index >= self.inventory.len() || !self.inventory[index].is_consumable() {
+            return None;
+        }
+
+        match self.inventory.remove(index) {
+            Item::Consumable(consumable) => Some(consumable),
+            _ => unreachable!()
+        }
+    }
+
+    // Stronger players heal faster, smarter ones get mana back faster
+    pub fn regenerate(&mut self) {
+        let health_rate = self.base_stats.strength * 2;
+        let mana_rate = self.base_stats.intelligence * 2;
+        self.regen.tick(&mut self.curr_stats, &self.base_stats, health_rate, mana_rate);
+    }
+
+    // Nothing left to recover
+    pub fn rested(&self) -> bool {
+        self.curr_stats.health >= self.base_stats.health &&
+        self.curr_stats.mana >= self.base_stats.mana
+    }
+
+    // Returns how much was actually healed
+    pub fn heal(&mut self, amount : i32) -> i32 {
+        let before = self.curr_stats.health;
+        self.curr_stats.health = (before + amount).min(self.base_stats.health);
+        self.curr_stats.health - before
+    }
+
+    pub fn restore_mana(&mut self, amount : i32) -> i32 {
+        let before = self.curr_stats.mana;
+        self.curr_stats.mana = (before + amount).min(self.base_stats.mana);
+        self.curr_stats.mana - before
+    }
+
+    // False if there isn't enough to pay for it
+    pub fn spend_mana(&mut self, cost : i32) -> bool {
+        if self.curr_stats.mana < cost {
+            return false;
+        }
+        self.curr_stats.mana -= cost;
+        true
+    }
+
+    // Wear something from the pack. Whatever it replaces goes
+    // back in the pack. Returns the name of what was put on
+    pub fn equip(&mut self, index : usize) -> Option<String> {
+        if index >= self.inventory.len() || !self.inventory[index].is_gear() {
+            return None;
+        }
+
+        let gear = match self.inventory.remove(index) {
+            Item::Gear(gear) => gear,
+            _ => unreachable!()
+        };
+
+        Some(self.wear(gear))
+    }
+
+    // Straight onto the player without going through the pack.
+    // Whatever was in the slot goes in the pack instead
+    pub fn wear(&mut self, gear : Gear) -> String {
+        let name = String::from(gear.name());
+        self.base_stats.add(gear.bonus());
+        self.curr_stats.add(gear.bonus());
+        if let Some(old) = self.equipment.equip(gear) {
+            self.remove_bonus(&old);
+            self.inventory.push(Item::Gear(old));
+        }
+
+        name
+    }
+
+    pub fn unequip(&mut self, slot : EquipSlot) -> Option<String> {
+        let gear = self.equipment.unequip(slot)?;
+        let name = String::from(gear.name());
+        self.remove_bonus(&gear);
+        self.inventory.push(Item::Gear(gear));
+
+        Some(name)
+    }
+
+    // Taking gear off never kills you
+    fn remove_bonus(&mut self, gear : &Gear) {
+        self.base_stats.sub(gear.bonus());
+        self.curr_stats.sub(gear.bonus());
+        self.curr_stats.health = self.curr_stats.health.max(1);
+    }
+
     pub fn target(&self) -> Option<usize> {
         self.target
     }
@@ -111,6 +267,20 @@ impl Player {
     }
 }
 
+impl Drawable for Player {
+    fn draw(&self) -> DrawOutput {
+        DrawOutput {
+            position : self.pos,
+            fg : Vec3::new(255, 255, 255),
+            bg : Vec3::new(95, 95, 95),
+            icon : '@'
+        }
+    }
+
+    fn kind(&self) -> &str {
+        "player"
+    }
+}
 
 impl fmt::Display for Player {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
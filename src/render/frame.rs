@@ -0,0 +1,98 @@
+use utils::{Vec2, Vec3};
+
+// Same as the "primary" and "view" colors in assets/theme.toml
+pub const DEFAULT_FG : Vec3<u8> = Vec3 { x : 255, y : 255, z : 255 };
+pub const DEFAULT_BG : Vec3<u8> = Vec3 { x : 95, y : 95, z : 95 };
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cell {
+    pub glyph : char,
+    pub fg : Vec3<u8>,
+    pub bg : Vec3<u8>,
+    pub bold : bool
+}
+
+impl Cell {
+    pub fn new(glyph : char, fg : Vec3<u8>, bg : Vec3<u8>) -> Cell {
+        Cell {
+            glyph,
+            fg,
+            bg,
+            bold : false
+        }
+    }
+}
+
+// A whole screen worth of cells. Nothing in here knows about
+// terminals, backends just copy the cells out
+pub struct Frame {
+    size : Vec2<usize>,
+    cells : Vec<Cell>
+}
+
+impl Frame {
+    pub fn new(size : Vec2<usize>) -> Frame {
+        Frame {
+            size,
+            cells : vec![Cell::new(' ', DEFAULT_FG, DEFAULT_BG); size.x * size.y]
+        }
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn get(&self, pos : Vec2<usize>) -> Option<&Cell> {
+        if pos.x < self.size.x && pos.y < self.size.y {
+            return self.cells.get(pos.x + pos.y * self.size.x);
+        }
+
+        None
+    }
+
+    // Anything off the edge of the frame is dropped
+    pub fn put(&mut self, pos : Vec2<usize>, cell : Cell) {
+        if pos.x < self.size.x && pos.y < self.size.y {
+            self.cells[pos.x + pos.y * self.size.x] = cell;
+        }
+    }
+
+    pub fn print(&mut self, pos : Vec2<usize>, text : &str, fg : Vec3<u8>, bg : Vec3<u8>) {
+        for (i, glyph) in text.chars().enumerate() {
+            self.put(Vec2::new(pos.x + i, pos.y), Cell::new(glyph, fg, bg));
+        }
+    }
+
+    pub fn print_bold(&mut self, pos : Vec2<usize>, text : &str) {
+        for (i, glyph) in text.chars().enumerate() {
+            let mut cell = Cell::new(glyph, DEFAULT_FG, DEFAULT_BG);
+            cell.bold = true;
+            self.put(Vec2::new(pos.x + i, pos.y), cell);
+        }
+    }
+
+    pub fn hline(&mut self, pos : Vec2<usize>, len : usize, glyph : char) {
+        for i in 0..len {
+            self.put(Vec2::new(pos.x + i, pos.y), Cell::new(glyph, DEFAULT_FG, DEFAULT_BG));
+        }
+    }
+
+    pub fn vline(&mut self, pos : Vec2<usize>, len : usize, glyph : char) {
+        for i in 0..len {
+            self.put(Vec2::new(pos.x, pos.y + i), Cell::new(glyph, DEFAULT_FG, DEFAULT_BG));
+        }
+    }
+
+    // Plain text version of the frame. Colors are dropped and 
+    // trailing spaces are trimmed so snapshots are easy to write
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        for y in 0..self.size.y {
+            let row = &self.cells[y * self.size.x..(y + 1) * self.size.x];
+            let line : String = row.iter().map(|c| c.glyph).collect();
+            lines.push(String::from(line.trim_end()));
+        }
+
+        lines.join("\n")
+    }
+}
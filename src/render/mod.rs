@@ -0,0 +1,7 @@
+mod frame;
+mod screen;
+mod snapshot;
+
+pub use self::frame::{Frame, Cell, DEFAULT_FG, DEFAULT_BG};
+pub use self::screen::Screen;
+pub use self::snapshot::SnapshotBackend;
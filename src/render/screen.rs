@@ -0,0 +1,401 @@
+use utils::{Vec2, Vec3};
+use camera::Camera;
+use layout::Layout;
+use entity::{Drawable, DrawOutput, DrawManager, DrawCell, Layer, StatBlock, StatusEffects};
+use world::{WorldNode, Dungeon, ThemeSet, TileType};
+use render::{Frame, Cell, DEFAULT_FG, DEFAULT_BG};
+use Game;
+
+const GREEN : Vec3<u8> = Vec3 { x : 0, y : 255, z : 0 };
+const TARGET_BG : Vec3<u8> = Vec3 { x : 50, y : 50, z : 50 };
+const STACK_ICON : char = '&';
+const STATUS_FG : Vec3<u8> = Vec3 { x : 255, y : 140, z : 0 };
+const PATH_FG : Vec3<u8> = Vec3 { x : 255, y : 255, z : 0 };
+const PATH_ICON : char = '*';
+const CURSOR_BG : Vec3<u8> = Vec3 { x : 200, y : 200, z : 0 };
+const MENU_BG : Vec3<u8> = Vec3 { x : 30, y : 30, z : 30 };
+const MENU_SELECTED_BG : Vec3<u8> = Vec3 { x : 0, y : 120, z : 200 };
+
+// Turns the game state into a Frame. Holds the bits of drawing 
+// state that arent part of the game like the themes and camera
+pub struct Screen {
+    themes : ThemeSet,
+    camera : Camera
+}
+
+impl Screen {
+    pub fn new(game : &Game) -> Screen {
+        let mut screen = Screen {
+            themes : ThemeSet::new(),
+            camera : Camera::new(game.layout().viewport.size)
+        };
+        screen.update(game);
+
+        screen
+    }
+
+    // Call whenever the game changes so the camera keeps up with
+    // the player and the size of the viewport
+    pub fn update(&mut self, game : &Game) {
+        self.camera.set_viewport(game.layout().viewport.size);
+
+        match game.world().active_node() {
+            WorldNode::DungeonNode(ref dungeon) => {
+                        let room = dungeon.active_room();
+                        let room_size = Vec2::new(room.width(), room.height());
+                        // Follow the look cursor around big rooms
+                        let focus = match game.look_cursor() {
+                            Some(cursor) => cursor,
+                            None => *game.player().position()
+                        };
+                        self.camera.update(room_size, focus);
+            }
+        }   
+    }
+
+    // Screen position to room position
+    pub fn to_room(&self, screen : Vec2<usize>) -> Option<Vec2<usize>> {
+        self.camera.to_room(screen)
+    }
+
+    pub fn render(&self, game : &Game) -> Frame {
+        let layout = game.layout();
+        let mut frame = Frame::new(layout.screen);
+
+        if layout.too_small() {
+            let min = Layout::min_size();
+            let msg = format!("Terminal too small: need {}x{}, have {}x{}", 
+                              min.x, min.y, layout.screen.x, layout.screen.y);
+            frame.print(Vec2::new(0, 0), &msg, DEFAULT_FG, DEFAULT_BG);
+            return frame;
+        }
+
+        match game.world().active_node() {
+            WorldNode::DungeonNode(ref dungeon) => self.draw_dungeon_room(&mut frame, game, dungeon),
+        }   
+
+        self.draw_log(&mut frame, game);
+        self.draw_info(&mut frame, game);
+        self.draw_menu(&mut frame, game);
+
+        frame
+    }
+
+    fn draw_stats(&self, frame : &mut Frame, start : Vec2<usize>, name : &str,
+                  curr_stats : &StatBlock, base_stats : &StatBlock) 
+    {
+        let mut x = start.x;
+        let mut y = start.y;
+        frame.print_bold(Vec2::new(x, y), "Name:");
+        x += 6;
+        frame.print(Vec2::new(x, y), name, GREEN, DEFAULT_BG);
+        y += 1;
+        x = start.x;
+
+        frame.print_bold(Vec2::new(x, y), "Health: ");
+        x += 8;
+
+        let health = format!("{}/{}", curr_stats.health, base_stats.health);
+        frame.print(Vec2::new(x, y), &health, GREEN, DEFAULT_BG);
+    }
+
+    // The rest of the stat block under draw_stats, current over base
+    // like health is. Look mode has the room to show it
+    fn draw_stat_details(&self, frame : &mut Frame, start : Vec2<usize>,
+                         curr_stats : &StatBlock, base_stats : &StatBlock)
+    {
+        let x = start.x;
+        let y = start.y;
+        let stat = |name : &str, curr : i32, base : i32| format!("{}: {}/{}", name, curr, base);
+
+        frame.print_bold(Vec2::new(x, y), "Mana: ");
+        let mana = format!("{}/{}", curr_stats.mana, base_stats.mana);
+        frame.print(Vec2::new(x + 6, y), &mana, GREEN, DEFAULT_BG);
+
+        let strength = stat("Str", curr_stats.strength, base_stats.strength);
+        frame.print(Vec2::new(x, y + 1), &strength, GREEN, DEFAULT_BG);
+        let dexterity = stat("Dex", curr_stats.dexterity, base_stats.dexterity);
+        frame.print(Vec2::new(x + strength.len() + 1, y + 1), &dexterity, GREEN, DEFAULT_BG);
+
+        let intelligence = stat("Int", curr_stats.intelligence, base_stats.intelligence);
+        frame.print(Vec2::new(x, y + 2), &intelligence, GREEN, DEFAULT_BG);
+    }
+
+    // One line listing effects and turns left, ie "Burning 2 Poisoned 4"
+    fn draw_status(&self, frame : &mut Frame, start : Vec2<usize>, status : &StatusEffects) {
+        if status.effects().is_empty() {
+            return;
+        }
+
+        let names : Vec<String> = status.effects().iter()
+                                        .map(|e| format!("{} {}", e.kind.name(), e.turns))
+                                        .collect();
+        frame.print_bold(start, "Status: ");
+        frame.print(Vec2::new(start.x + 8, start.y), &names.join(" "), STATUS_FG, DEFAULT_BG);
+    }
+
+    fn draw_player_info(&self, frame : &mut Frame, game : &Game, start : Vec2<usize>) {
+        let player = game.player();
+        self.draw_stats(frame, start, player.name(), 
+                        player.current_stats(), player.base_stats());
+        self.draw_status(frame, Vec2::new(start.x, start.y + 2), player.status());
+    }
+
+    fn draw_target_info(&self, frame : &mut Frame, game : &Game, start : Vec2<usize>) {
+        let curr_stats = game.target_current_stats().unwrap();
+        let base_stats = game.target_base_stats().unwrap();
+        let name = game.target_name().unwrap();
+        self.draw_stats(frame, start, name, curr_stats, base_stats);
+        self.draw_status(frame, Vec2::new(start.x, start.y + 2), game.target_status().unwrap());
+    }
+
+    fn draw_info(&self, frame : &mut Frame, game : &Game) {
+        let panel = game.layout().side_panel;
+        let x = panel.origin.x;
+        let split = panel.size.y/2;
+
+        frame.vline(Vec2::new(x-1, 0), panel.size.y, '│');
+
+        frame.hline(Vec2::new(x, split), panel.size.x, '─');
+
+        frame.print(Vec2::new(x, 0), "Player:", DEFAULT_FG, DEFAULT_BG);
+
+        self.draw_player_info(frame, game, Vec2::new(x, 1));
+
+        // Looking takes over the target half of the panel
+        if let Some(cursor) = game.look_cursor() {
+            frame.print(Vec2::new(x, split+1), "Look:", DEFAULT_FG, DEFAULT_BG);
+            self.draw_look_info(frame, game, cursor, Vec2::new(x, split+2));
+            return;
+        }
+
+        frame.print(Vec2::new(x, split+1), "Target:", DEFAULT_FG, DEFAULT_BG);
+        if game.active_target() {
+            self.draw_target_info(frame, game, Vec2::new(x, split+2));
+        }
+    }
+
+    fn draw_look_info(&self, frame : &mut Frame, game : &Game, 
+                      cursor : Vec2<usize>, start : Vec2<usize>) 
+    {
+        let dungeon = match game.world().active_node() {
+            WorldNode::DungeonNode(ref dungeon) => dungeon
+        };
+        let room = dungeon.active_room();
+        let tile = match room.tile(cursor) {
+            Some(tile) => tile,
+            None => return
+        };
+
+        let x = start.x;
+        let mut y = start.y;
+
+        let visible = dungeon.field_of_view(*game.player().position());
+        let in_sight = visible[cursor.x + cursor.y * room.width()];
+
+        frame.print_bold(Vec2::new(x, y), "Tile: ");
+        frame.print(Vec2::new(x + 6, y), &tile.id.name().replace("_", " "), GREEN, DEFAULT_BG);
+        y += 1;
+
+        frame.print_bold(Vec2::new(x, y), "Visible: ");
+        frame.print(Vec2::new(x + 9, y), if in_sight { "yes" } else { "no" }, GREEN, DEFAULT_BG);
+        y += 1;
+
+        // Don't give away what's out of sight
+        if !in_sight {
+            return;
+        }
+
+        if game.player().collision(cursor) {
+            let player = game.player();
+            self.draw_stats(frame, Vec2::new(x, y), player.name(),
+                            player.current_stats(), player.base_stats());
+            self.draw_stat_details(frame, Vec2::new(x, y + 2), 
+                                   player.current_stats(), player.base_stats());
+            self.draw_status(frame, Vec2::new(x, y + 5), player.status());
+            y += 6;
+        }
+
+        for e in dungeon.get_entities().values() {
+            if e.collision(cursor) {
+                self.draw_stats(frame, Vec2::new(x, y), e.name(), 
+                                e.current_stats(), e.base_stats());
+                self.draw_stat_details(frame, Vec2::new(x, y + 2), 
+                                       e.current_stats(), e.base_stats());
+                self.draw_status(frame, Vec2::new(x, y + 5), e.status());
+                y += 6;
+            }
+        }
+
+        if !tile.corpses.is_empty() {
+            let corpses = format!("{}", tile.corpses.len());
+            frame.print_bold(Vec2::new(x, y), "Corpses: ");
+            frame.print(Vec2::new(x + 9, y), &corpses, GREEN, DEFAULT_BG);
+            y += 1;
+        }
+
+        let items : Vec<&str> = room.items().iter()
+                                    .filter(|i| *i.position() == cursor)
+                                    .map(|i| i.item().name())
+                                    .collect();
+        if !items.is_empty() {
+            frame.print_bold(Vec2::new(x, y), "Items: ");
+            frame.print(Vec2::new(x + 7, y), &items.join(", "), GREEN, DEFAULT_BG);
+        }
+    }
+
+    // Box next to the clicked cell, kept inside the viewport
+    fn draw_menu(&self, frame : &mut Frame, game : &Game) {
+        let (pos, selected, actions) = match game.menu() {
+            Some(menu) => menu,
+            None => return
+        };
+        let cell = match self.camera.to_screen(pos) {
+            Some(cell) => cell,
+            None => return
+        };
+
+        let labels : Vec<String> = actions.iter().enumerate()
+                                          .map(|(i, a)| format!(" {}) {} ", i + 1, a.label()))
+                                          .collect();
+        let width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+        let viewport = game.layout().viewport.size;
+
+        let mut x = cell.x + 1;
+        if x + width > viewport.x {
+            x = cell.x.saturating_sub(width);
+        }
+        let mut y = cell.y;
+        if y + labels.len() > viewport.y {
+            y = viewport.y.saturating_sub(labels.len());
+        }
+
+        for (i, label) in labels.iter().enumerate() {
+            let bg = if i == selected { MENU_SELECTED_BG } else { MENU_BG };
+            let line = format!("{:width$}", label, width = width);
+            frame.print(Vec2::new(x, y + i), &line, DEFAULT_FG, bg);
+        }
+
+        let mut marker = *frame.get(cell).unwrap();
+        marker.bg = CURSOR_BG;
+        frame.put(cell, marker);
+    }
+
+    fn draw_log(&self, frame : &mut Frame, game : &Game) {
+        let log = game.layout().log;
+
+        // Draw Borders
+        frame.hline(Vec2::new(0, log.origin.y-1), log.size.x, '─');
+
+        let msgs = game.get_log_messages(log.size.y);
+        for (i,msg) in msgs.iter().enumerate() {
+            frame.print(Vec2::new(log.origin.x, log.origin.y+i), msg, DEFAULT_FG, DEFAULT_BG);
+        }
+    }
+
+    fn draw_cell(&self, frame : &mut Frame, cell : &DrawCell) {
+        let display = &cell.output;
+        if let Some(screen) = self.camera.to_screen(display.position) {
+            let mut out = Cell::new(display.icon, display.fg, display.bg);
+            // A pile of stuff on the floor gets its own glyph, something
+            // standing on a pile is just highlighted
+            if cell.stacked() {
+                match cell.layer {
+                    Layer::Corpse | Layer::Item => out.glyph = STACK_ICON,
+                    _ => out.bold = true
+                }
+            }
+            frame.put(screen, out);
+        }
+    }
+
+    fn draw_dungeon_room(&self, frame : &mut Frame, game : &Game, dungeon : &Dungeon) {
+        let player = game.player();
+
+        let room = dungeon.active_room();
+        let theme = self.themes.get(room.theme());
+
+        let header = format!("{} {}", dungeon.active_room_id(), theme.name());
+        frame.print(Vec2::new(0, 0), &header, DEFAULT_FG, DEFAULT_BG);
+
+        let visible = dungeon.field_of_view(*player.position());
+        let mut manager = DrawManager::new(Vec2::new(room.width(), room.height()));
+
+        for ( i, tile) in room.tiles().iter().enumerate() {
+            // Solid rock is just empty space
+            if let TileType::Rock = tile.id {
+                continue;
+            }
+
+            let mut display = theme.tile(&tile.id);
+            // Out of sight tiles are remembered but dimmed
+            if !visible[i] {
+                display.fg = dim(display.fg);
+                display.bg = dim(display.bg);
+            }
+
+            manager.push(Layer::Tile, DrawOutput {
+                position : Vec2::new(i % room.width(), i / room.width()),
+                fg : display.fg,
+                bg : display.bg,
+                icon : display.icon
+            });
+        } 
+
+        // Only things in sight get drawn on top of the map
+        let in_sight = |pos : Vec2<usize>| visible[pos.x + pos.y * room.width()];
+
+        for c in dungeon.get_corpses().values() {
+            let display = theme.entity(c.kind(), c.draw());
+            if in_sight(display.position) {
+                manager.push(Layer::Corpse, display);
+            }
+        }
+
+        for item in room.items() {
+            let display = theme.entity(item.kind(), item.draw());
+            if in_sight(display.position) {
+                manager.push(Layer::Item, display);
+            }
+        }
+
+        for (uuid, e) in dungeon.get_entities().iter() {
+            let mut display = theme.entity(e.kind(), e.draw());
+            if !in_sight(display.position) {
+                continue;
+            }
+
+            if player.target() == Some(*uuid) {
+                display.bg = TARGET_BG;
+            }
+            manager.push(Layer::Monster, display);
+        }
+        
+        manager.push(Layer::Player, theme.entity(player.kind(), player.draw()));
+
+        for pos in game.projectile_path() {
+            manager.push(Layer::Effect, DrawOutput {
+                position : pos,
+                fg : PATH_FG,
+                bg : DEFAULT_BG,
+                icon : PATH_ICON
+            });
+        }
+
+        for cell in manager.cells() {
+            self.draw_cell(frame, &cell);
+        }
+
+        if let Some(cursor) = game.look_cursor() {
+            if let Some(screen) = self.camera.to_screen(cursor) {
+                let mut cell = *frame.get(screen).unwrap();
+                cell.bg = CURSOR_BG;
+                frame.put(screen, cell);
+            }
+        }
+    }
+}
+
+fn dim(color : Vec3<u8>) -> Vec3<u8> {
+    Vec3::new(color.x / 2, color.y / 2, color.z / 2)
+}
@@ -0,0 +1,117 @@
+use {Game, GameOptions};
+use input::Input;
+use render::{Frame, Screen};
+use utils::seed;
+
+// Runs the game without a terminal. Feed it inputs and read back
+// the screen as plain text. The same seed and inputs always give
+// the same screen
+pub struct SnapshotBackend {
+    game : Game,
+    screen : Screen
+}
+
+impl SnapshotBackend {
+    pub fn new(options : GameOptions, game_seed : u64) -> SnapshotBackend {
+        seed(game_seed);
+        let game = Game::new(options);
+        let screen = Screen::new(&game);
+
+        SnapshotBackend {
+            game,
+            screen
+        }
+    }
+
+    pub fn handle_input(&mut self, input : &Input) {
+        self.game.handle_input(input);
+        self.screen.update(&self.game);
+    }
+
+    pub fn run(&mut self, inputs : &[Input]) {
+        for input in inputs {
+            self.handle_input(input);
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.screen.render(&self.game)
+    }
+
+    pub fn snapshot(&self) -> String {
+        self.frame().to_text()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use player::Class;
+
+    #[test]
+    fn walk_into_the_room() {
+        let options = GameOptions::new(60, 20, String::from("Bob"), Class::Warrior);
+        let mut backend = SnapshotBackend::new(options, 7);
+        backend.run(&[Input::Right, Input::Right, Input::Down, Input::Down]);
+
+        let expected = [
+        "7 Cave                               │Player:",
+        "                                     │Name: Bob",
+        "             ##########              │Health: 22/22",
+        "             #..'.....#              │",
+        "             #~.#.....#              │",
+        "             #~@#.....#              │",
+        "             #~~#.....#              │",
+        "             #..#.....#              │",
+        "             #..#.....#              │",
+        "             #.!#.....#              │",
+        "             #..#.....#              │──────────────────────",
+        "             ##### ####              │Target:",
+        "                                     │",
+        "                                     │",
+        "─────────────────────────────────────│",
+        "You open the door                    │",
+        "                                     │",
+        "                                     │",
+        "                                     │",
+        "                                     │",
+        ];
+        assert_eq!(backend.snapshot(), expected.join("\n"));
+    }
+
+    #[test]
+    fn look_then_open_door() {
+        let options = GameOptions::new(60, 20, String::from("Bob"), Class::Warrior);
+        let mut backend = SnapshotBackend::new(options, 7);
+        backend.run(&[Input::Key('x'), Input::Right, Input::Down, Input::Escape,
+                      Input::Right, Input::Right, Input::Down]);
+
+        let expected = [
+        "7 Cave                               │Player:",
+        "                                     │Name: Bob",
+        "             ##########              │Health: 22/22",
+        "             #..'.....#              │",
+        "             #~@#.....#              │",
+        "             #~.#.....#              │",
+        "             #~~#.....#              │",
+        "             #..#.....#              │",
+        "             #..#.....#              │",
+        "             #.!#.....#              │",
+        "             #..#.....#              │──────────────────────",
+        "             ##### ####              │Target:",
+        "                                     │",
+        "                                     │",
+        "─────────────────────────────────────│",
+        "You open the door                    │",
+        "                                     │",
+        "                                     │",
+        "                                     │",
+        "                                     │",
+        ];
+        assert_eq!(backend.snapshot(), expected.join("\n"));
+    }
+}
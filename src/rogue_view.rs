@@ -4,7 +4,8 @@ use super::player;
 use super::{ Game, GameOptions, Input};
 use utils;
 use super::input;
-use world::{WorldNode, Dungeon};
+use render::Screen;
+use item::EquipSlot;
 
 use self::cursive::Printer;
 use self::cursive::theme::{Color, ColorStyle, Effect};
@@ -12,253 +13,133 @@ use self::cursive::vec;
 use self::cursive::direction::Direction;
 use self::cursive::event::{Event, MouseEvent, MouseButton, EventResult, Key};
 
+// Cursive backend. All the actual drawing happens in render::Screen,
+// this just copies the frame to the terminal and turns cursive 
+// events into game input
 pub struct RogueView {
     game : Game,
+    screen : Screen,
     width : usize,
-    height : usize,
-    offset : utils::Vec2<usize>
+    height : usize
 }
 
 impl RogueView {
     pub fn new(size : vec::Vec2, name : String, class : player::Class) -> RogueView {
-        let options = GameOptions::new(60, 30, name, class);
+        let options = GameOptions::new(size.x, size.y, name, class);
 
-        let mut rogueview = RogueView {
-            game : Game::new(options),
-            width : size.x,
-            height : size.y,
-            offset : utils::Vec2::new(0,0)
-        };
-        rogueview.update_room_offset();
+        let game = Game::new(options);
+        let screen = Screen::new(&game);
 
-        rogueview
+        RogueView {
+            game,
+            screen,
+            width : size.x,
+            height : size.y
+        }
     }
 
     pub fn active_loot(&self) -> String {
         self.game.active_loot()
     }
 
-    fn draw_player_info(&self, start : vec::Vec2, printer: &Printer) {
-        let player = self.game.player();
-        let curr_stats = player.current_stats();
-        let base_stats = player.base_stats();
-
-        let green = Color::Rgb(0,255,0);
-        let bg = Color::Rgb(95,95,95);
-        let mut x = start.x;
-        let mut y = start.y;
-        printer.with_effect( Effect::Bold,
-            |printer| printer.print((x,y), "Name:")
-        );
-        x += 6;
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), player.name())
-        );
-        y += 1;
-        x = start.x;
-
-        printer.with_effect( Effect::Bold,
-            |printer| printer.print((x,y), "Health: ")
-        );
-
-        x += 8;
-
-        let curr_health = curr_stats.health.to_string() + "/";
-        let base_health = base_stats.health.to_string();
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), curr_health.as_ref())
-        );
-
-        x += curr_health.len();
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), base_health.as_ref())
-        );
+    // One line per slot, ie "Weapon: Short sword"
+    pub fn equipped(&self) -> Vec<String> {
+        let equipment = self.game.player().equipment();
+        EquipSlot::all().iter()
+                        .map(|slot| match equipment.get(*slot) {
+                            Some(gear) => format!("{}: {}", slot.name(), gear.name()),
+                            None => format!("{}: -", slot.name())
+                        })
+                        .collect()
     }
 
-    fn draw_target_info(&self, start : vec::Vec2, printer: &Printer) {
-        let curr_stats = self.game.target_current_stats().unwrap();
-        let base_stats = self.game.target_base_stats().unwrap();
-        let name = self.game.target_name().unwrap();
-
-        let green = Color::Rgb(0,255,0);
-        let bg = Color::Rgb(95,95,95);
-        let mut x = start.x;
-        let mut y = start.y;
-        printer.with_effect( Effect::Bold,
-            |printer| printer.print((x,y), "Name:")
-        );
-        x += 6;
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), name)
-        );
-        y += 1;
-        x = start.x;
-
-        printer.with_effect( Effect::Bold,
-            |printer| printer.print((x,y), "Health: ")
-        );
-
-        x += 8;
-
-        let curr_health = curr_stats.health.to_string() + "/";
-        let base_health = base_stats.health.to_string();
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), curr_health.as_ref())
-        );
-
-        x += curr_health.len();
-        printer.with_color(
-            ColorStyle::new(green, bg),
-            |printer| printer.print((x,y), base_health.as_ref())
-        );
+    // Gear in the pack along with its inventory index
+    pub fn pack_gear(&self) -> Vec<(String, usize)> {
+        self.game.player().inventory().iter()
+                          .enumerate()
+                          .filter(|&(_, item)| item.is_gear())
+                          .map(|(i, item)| (String::from(item.name()), i))
+                          .collect()
     }
 
-    fn draw_info(&self, printer: &Printer) {
-        let vp_width = self.game.viewport_width();
-
-        printer.print_vline( (vp_width, 0), self.width, "│");
-
-        printer.print_hline((vp_width+1,self.height/2), self.width-vp_width, "─");
-
-        printer.print((vp_width+1, 0), "Player:");
-
-        printer.print((vp_width+1, self.height/2+1), "Target:");
-
-        self.draw_player_info(vec::Vec2::new(vp_width+1, 1), printer);
-        if self.game.active_target() {
-            self.draw_target_info(vec::Vec2::new(vp_width+1, self.height/2+2), printer);
-        }
-
+    // Consumables in the pack along with their inventory index
+    pub fn pack_consumables(&self) -> Vec<(String, usize)> {
+        let inventory = self.game.player().inventory();
+        self.game.consumables().iter()
+                 .enumerate()
+                 .map(|(key, i)| (format!("{}) {}", key + 1, inventory[*i].name()), *i))
+                 .collect()
     }
 
-    fn draw_log(&self, printer: &Printer) {
-        let vp_width = self.game.viewport_width();
-        let vp_height = self.game.viewport_height();
-
-        // Draw Borders
-        printer.print_hline((0,vp_height), vp_width, "─");
-
-        let log_size = self.height - vp_height-1;
-
-        let msgs = self.game.get_log_messages(log_size);
-        for (i,msg) in msgs.iter().enumerate() {
-            printer.print((0, vp_height+1+i), msg);
-        }
+    pub fn use_item(&mut self, index : usize) {
+        self.game.use_item(index);
+        self.screen.update(&self.game);
     }
 
-    fn draw_dungeon_room(&self, dungeon : &Dungeon, printer: &Printer) {
-        let player = self.game.player();
-        let vp_width = self.game.viewport_width();
-        let vp_height = self.game.viewport_height();
-
-        let room = dungeon.active_room();
-
-        printer.print((0,0), &dungeon.active_room_id().to_string());
-
-        for ( i, tile) in room.tiles().iter().enumerate() {
-            let x = i % room.width();
-            let y = i / room.width();
-
-            let display = tile.id.value();
-            let symbol = display.icon.to_string();
-            let fg = Color::Rgb( display.fg.x, display.fg.y, display.fg.z );
-            let bg = Color::Rgb( display.bg.x, display.bg.y, display.bg.z );
-
-            printer.with_color(
-                ColorStyle::new(fg, bg),
-                |printer| printer.print(
-                                (self.offset.x + x, self.offset.y + y), &symbol),
-            );
-        } 
-
-        let corpses = dungeon.get_corpses();
-        for (uuid, c) in corpses.iter() {
-            let display = c.draw();
-            let pos = display.position;
-
-            let symbol = display.icon.to_string();
-            let fg = Color::Rgb( display.fg.x, display.fg.y, display.fg.z );
-            let bg = Color::Rgb( display.bg.x, display.bg.y, display.bg.z );
-            printer.with_color(
-                ColorStyle::new(fg, bg),
-                |printer| printer.print(
-                                (self.offset.x + pos.x, self.offset.y + pos.y), &symbol),
-            );
-        }
-
-        // draw entities
-        let entities = dungeon.get_entities();
-        for (uuid, e) in entities.iter() {
-            let display = e.draw();
-            let pos = display.position;
+    pub fn equip(&mut self, index : usize) {
+        self.game.equip(index);
+        self.screen.update(&self.game);
+    }
 
-            let symbol = display.icon.to_string();
-            let fg = Color::Rgb( display.fg.x, display.fg.y, display.fg.z );
-            let mut bg = Color::Rgb( display.bg.x, display.bg.y, display.bg.z );
-            let result = self.game.player().target();
-            if let Some(target) = result {
-                if target == *uuid {
-                    bg = Color::Rgb(50,50,50);
-                }
-            }
-            printer.with_color(
-                ColorStyle::new(fg, bg),
-                |printer| printer.print(
-                                (self.offset.x + pos.x, self.offset.y + pos.y), &symbol),
-            );
+    pub fn unequip(&mut self, slot : usize) {
+        if let Some(slot) = EquipSlot::all().get(slot) {
+            self.game.unequip(*slot);
+            self.screen.update(&self.game);
         }
-        
-        // Draw Player
-        let pos = player.position();
-        let pos_x = self.offset.x + pos.x;
-        let pos_y = self.offset.y + pos.y;
-        printer.print((pos_x, pos_y), "@");
     }
 
-    fn update_room_offset(&mut self) {
-        // Calculate offset
-        let world = self.game.world();
-
-        match world.active_node() {
-            WorldNode::DungeonNode(ref dungeon) => {
-                        let room = dungeon.active_room();
-                        
-                        let vp_width = self.game.viewport_width();
-                        let vp_height = self.game.viewport_height();
-
-                        let x_offset = vp_width/2 - room.width()/2;
-                        let y_offset = vp_height/2 - room.height()/2;
-                        self.offset = utils::Vec2::new(x_offset, y_offset);
-            }
-            _ => println!("No draw for this not type yet"),
-        }   
+    fn resize(&mut self, size : vec::Vec2) {
+        self.width = size.x;
+        self.height = size.y;
+        self.game.resize(size.x, size.y);
+        self.screen.update(&self.game);
     }
 }
 
 impl cursive::view::View for RogueView {
     fn draw(&self, printer: &Printer) {
-        let world = self.game.world();
-
-        match world.active_node() {
-            WorldNode::DungeonNode(ref dungeon) => self.draw_dungeon_room(dungeon, printer),
-            _ => println!("No draw for this not type yet"),
-        }   
-
-        self.draw_log(printer);
-        self.draw_info(printer);
-
+        let frame = self.screen.render(&self.game);
+        let size = frame.size();
+
+        // Just copy the frame out cell by cell
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let cell = frame.get(utils::Vec2::new(x, y)).unwrap();
+                let fg = Color::Rgb( cell.fg.x, cell.fg.y, cell.fg.z );
+                let bg = Color::Rgb( cell.bg.x, cell.bg.y, cell.bg.z );
+                let symbol = cell.glyph.to_string();
+
+                printer.with_color(
+                    ColorStyle::new(fg, bg),
+                    |printer| {
+                        if cell.bold {
+                            printer.with_effect(Effect::Bold, |printer| printer.print((x, y), &symbol));
+                        }
+                        else {
+                            printer.print((x, y), &symbol);
+                        }
+                    }
+                );
+            }
+        }
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
         true
     }
 
+    fn layout(&mut self, size : vec::Vec2) {
+        if size.x != self.width || size.y != self.height {
+            self.resize(size);
+        }
+    }
+
     fn on_event(&mut self, event: Event) -> EventResult {   
+        // Nothing to play on
+        if self.game.layout().too_small() {
+            return EventResult::Ignored;
+        }
+
         let mut input : input::Input = Input::Unknown;
         if let Event::Key(key) = event {     
             input = match key {
@@ -266,9 +147,15 @@ impl cursive::view::View for RogueView {
                 Key::Left => Input::Left,
                 Key::Up => Input::Up,
                 Key::Down => Input::Down,
+                Key::Esc => Input::Escape,
+                Key::Tab => Input::Tab,
+                Key::Enter => Input::Enter,
                 _ => Input::Unknown
             };
         }
+        else if let Event::Char(c) = event {
+            input = Input::Key(c);
+        }
         else if let Event::Mouse{offset ,position, event} = event {
             let new_event = match event {
                 MouseEvent::Press(button) => input::MouseEvent::Press(
@@ -296,23 +183,22 @@ impl cursive::view::View for RogueView {
                 MouseEvent::WheelDown => input::MouseEvent::WheelUp,
             };
 
-            let mut mouse_position = utils::Vec2::new(position.x, position.y);
-            if position.x >= self.offset.x {
-                mouse_position.x -= self.offset.x;
-            }
-            if position.y >= self.offset.y {
-                mouse_position.y -= self.offset.y;
+            // Clicks outside the room are ignored
+            if position.x >= offset.x && position.y >= offset.y {
+                let screen = utils::Vec2::new(position.x - offset.x, position.y - offset.y);
+                if let Some(room_position) = self.screen.to_room(screen) {
+                    input = Input::Mouse{
+                        offset : utils::Vec2::new(offset.x,offset.y),
+                        position : room_position,
+                        event : new_event,
+                    };
+                }
             }
-            input = Input::Mouse{
-                offset : utils::Vec2::new(offset.x,offset.y),
-                position : mouse_position,
-                event : new_event,
-            };
         }
 
         if input != Input::Unknown {
             self.game.handle_input(&input);
-            self.update_room_offset();
+            self.screen.update(&self.game);
             return EventResult::Consumed(None);
         }
 
@@ -0,0 +1,32 @@
+use utils::Vec2;
+use entity::{Attack, AttackType, ElementalType};
+use player::Player;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Spell {
+    Firebolt
+}
+
+impl Spell {
+    pub fn name(&self) -> &str {
+        match *self {
+            Spell::Firebolt => "Firebolt"
+        }
+    }
+
+    pub fn mana_cost(&self) -> i32 {
+        match *self {
+            Spell::Firebolt => 4
+        }
+    }
+
+    // Spells hit harder the smarter you are
+    pub fn attack(&self, player : &Player, atk_pos : Vec2<usize>) -> Attack {
+        let intelligence = player.current_stats().intelligence;
+        match *self {
+            Spell::Firebolt => Attack::new(AttackType::Elemental(ElementalType::Fire), 
+                                           intelligence / 2 + 2, 
+                                           atk_pos)
+        }
+    }
+}
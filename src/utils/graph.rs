@@ -1,3 +1,6 @@
+use std::collections::{VecDeque, BinaryHeap};
+use std::cmp::Reverse;
+
 type NodeId = usize;
 
 
@@ -7,8 +10,17 @@ pub struct Node<T> {
     pub data: T
 }
 
+impl<T> Node<T> {
+    pub fn neighbors(&self) -> &Vec<NodeId> {
+        &self.neighbors
+    }
+}
+
+// Edges go one way, use connect/disconnect for two way ones.
+// Removing a node leaves a hole so ids handed out earlier
+// still point at the same thing
 pub struct Graph<T> {
-    nodes : Vec<Node<T>>
+    nodes : Vec<Option<Node<T>>>
 }
 
 impl<T> Graph<T> {
@@ -18,28 +30,42 @@ impl<T> Graph<T> {
         }
     }
 
+    // Every id ever handed out, removed nodes included.
+    // Good for sizing per node lookup tables
     pub fn len(&self) -> usize {
         self.nodes.len()
     }
 
+    pub fn node_count(&self) -> usize {
+        self.nodes.iter().filter(|n| n.is_some()).count()
+    }
+
+    pub fn contains(&self, node : NodeId) -> bool {
+        self.get(node).is_some()
+    }
+
     pub fn new_node(&mut self, data: T) -> NodeId {
         let next_index = self.nodes.len();
 
         self.nodes.push(
-            Node {
+            Some(Node {
                 neighbors : Vec::new(),
                 data
-            }
+            })
         );
         next_index
     }
 
+    // Adding an edge that's already there does nothing
     pub fn add_neighbor(&mut self, node : NodeId, neighbor : NodeId) -> Result<(), &str> {
-        if node != neighbor && 
-           node < self.nodes.len() && 
-           neighbor < self.nodes.len() 
+        if node != neighbor &&
+           self.contains(node) &&
+           self.contains(neighbor)
         {
-            self.nodes[node].neighbors.push(neighbor);
+            let neighbors = &mut self.nodes[node].as_mut().unwrap().neighbors;
+            if !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
             return Ok(());
         }
 
@@ -47,28 +73,353 @@ impl<T> Graph<T> {
     }
 
     pub fn add_new_neighbor(&mut self, node : NodeId, data : T) -> Result<NodeId, &str> {
-        if node < self.nodes.len() {
+        if self.contains(node) {
             let neighbor = self.new_node(data);
-            self.nodes[node].neighbors.push(neighbor);
+            self.nodes[node].as_mut().unwrap().neighbors.push(neighbor);
             return Ok(neighbor);
         }
 
         Err("Invalid NodeId")
     }
 
-    pub fn get_neighbors(&self, node : NodeId) -> Option<&Vec<NodeId>> {
-        if node < self.nodes.len() {
-            return Some(&self.nodes[node].neighbors);
+    // Edge both ways
+    pub fn connect(&mut self, a : NodeId, b : NodeId) -> Result<(), &str> {
+        if a == b || !self.contains(a) || !self.contains(b) {
+            return Err("Invalid NodeId");
+        }
+
+        self.add_neighbor(a, b).unwrap();
+        self.add_neighbor(b, a).unwrap();
+        Ok(())
+    }
+
+    pub fn has_edge(&self, node : NodeId, neighbor : NodeId) -> bool {
+        match self.get(node) {
+            Some(n) => n.neighbors.contains(&neighbor),
+            None => false
+        }
+    }
+
+    // False if there was no such edge
+    pub fn remove_edge(&mut self, node : NodeId, neighbor : NodeId) -> bool {
+        if !self.has_edge(node, neighbor) {
+            return false;
+        }
+
+        self.nodes[node].as_mut().unwrap().neighbors.retain(|n| *n != neighbor);
+        true
+    }
+
+    pub fn disconnect(&mut self, a : NodeId, b : NodeId) -> bool {
+        let removed = self.remove_edge(a, b);
+        self.remove_edge(b, a) || removed
+    }
+
+    // Takes out the node and every edge pointing at it
+    pub fn remove_node(&mut self, node : NodeId) -> Option<T> {
+        let removed = self.nodes.get_mut(node)?.take()?;
+        for other in self.nodes.iter_mut() {
+            if let Some(ref mut other) = *other {
+                other.neighbors.retain(|n| *n != node);
+            }
         }
 
-        None
+        Some(removed.data)
+    }
+
+    pub fn get_neighbors(&self, node : NodeId) -> Option<&Vec<NodeId>> {
+        self.get(node).map(|n| &n.neighbors)
     }
 
     pub fn get(&self, node : NodeId) -> Option<&Node<T>> {
-        self.nodes.get(node)
+        match self.nodes.get(node) {
+            Some(&Some(ref n)) => Some(n),
+            _ => None
+        }
     }
 
     pub fn get_mut(&mut self, node : NodeId) -> Option<&mut Node<T>> {
-        self.nodes.get_mut(node)
+        match self.nodes.get_mut(node) {
+            Some(&mut Some(ref mut n)) => Some(n),
+            _ => None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node<T>)> {
+        self.nodes.iter()
+                  .enumerate()
+                  .filter_map(|(id, n)| n.as_ref().map(|n| (id, n)))
+    }
+
+    // Every edge as (from, to). Two way edges show up twice
+    pub fn edges<'a>(&'a self) -> impl Iterator<Item = (NodeId, NodeId)> + 'a {
+        self.iter()
+            .flat_map(|(id, n)| n.neighbors.iter().map(move |neighbor| (id, *neighbor)))
+    }
+
+    // Breadth first from start. Each reached node gets the node
+    // it was reached from, start points at itself
+    fn search(&self, start : NodeId) -> Vec<Option<NodeId>> {
+        let mut came_from = vec![None; self.nodes.len()];
+        if !self.contains(start) {
+            return came_from;
+        }
+
+        let mut queue = VecDeque::new();
+        came_from[start] = Some(start);
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            for neighbor in &self.get(node).unwrap().neighbors {
+                if came_from[*neighbor].is_none() {
+                    came_from[*neighbor] = Some(node);
+                    queue.push_back(*neighbor);
+                }
+            }
+        }
+
+        came_from
     }
-}
\ No newline at end of file
+
+    fn trace(came_from : &Vec<Option<NodeId>>, start : NodeId, end : NodeId) -> Option<Vec<NodeId>> {
+        came_from.get(end).cloned()??;
+
+        let mut path = vec![end];
+        let mut node = end;
+        while node != start {
+            node = came_from[node].unwrap();
+            path.push(node);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    // Everything that can be got to from start, start included
+    pub fn reachable(&self, start : NodeId) -> Vec<NodeId> {
+        self.search(start).iter()
+                          .enumerate()
+                          .filter(|&(_, from)| from.is_some())
+                          .map(|(id, _)| id)
+                          .collect()
+    }
+
+    pub fn is_reachable(&self, start : NodeId, end : NodeId) -> bool {
+        match self.search(start).get(end) {
+            Some(from) => from.is_some(),
+            None => false
+        }
+    }
+
+    // Hops from start to every node, None if it can't be reached
+    pub fn distances(&self, start : NodeId) -> Vec<Option<usize>> {
+        let came_from = self.search(start);
+        let mut distances = vec![None; self.nodes.len()];
+        for id in 0..self.nodes.len() {
+            if let Some(path) = Graph::<T>::trace(&came_from, start, id) {
+                distances[id] = Some(path.len() - 1);
+            }
+        }
+
+        distances
+    }
+
+    // Fewest hops, both ends included
+    pub fn shortest_path(&self, start : NodeId, end : NodeId) -> Option<Vec<NodeId>> {
+        Graph::<T>::trace(&self.search(start), start, end)
+    }
+
+    // Path to the closest node is_goal likes, both ends included
+    pub fn nearest<F>(&self, start : NodeId, is_goal : F) -> Option<Vec<NodeId>>
+        where F : Fn(NodeId, &T) -> bool
+    {
+        let came_from = self.search(start);
+        let mut best : Option<Vec<NodeId>> = None;
+        for (id, n) in self.iter() {
+            if !is_goal(id, &n.data) {
+                continue;
+            }
+            if let Some(path) = Graph::<T>::trace(&came_from, start, id) {
+                if best.as_ref().map_or(true, |b| path.len() < b.len()) {
+                    best = Some(path);
+                }
+            }
+        }
+
+        best
+    }
+
+    // Dijkstra, cost gives the price of going from one node to the next
+    pub fn shortest_path_by<F>(&self, start : NodeId, end : NodeId, cost : F) -> Option<Vec<NodeId>>
+        where F : Fn(NodeId, NodeId) -> usize
+    {
+        if !self.contains(start) || !self.contains(end) {
+            return None;
+        }
+
+        let mut best = vec![None; self.nodes.len()];
+        let mut came_from = vec![None; self.nodes.len()];
+        let mut queue = BinaryHeap::new();
+        best[start] = Some(0);
+        came_from[start] = Some(start);
+        queue.push(Reverse((0, start)));
+
+        while let Some(Reverse((total, node))) = queue.pop() {
+            if node == end {
+                break;
+            }
+            if best[node].map_or(false, |b| total > b) {
+                continue;
+            }
+
+            for neighbor in &self.get(node).unwrap().neighbors {
+                let next = total + cost(node, *neighbor);
+                if best[*neighbor].map_or(true, |b| next < b) {
+                    best[*neighbor] = Some(next);
+                    came_from[*neighbor] = Some(node);
+                    queue.push(Reverse((next, *neighbor)));
+                }
+            }
+        }
+
+        Graph::<T>::trace(&came_from, start, end)
+    }
+
+    // Groups of nodes joined up by edges going either way
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut links : Vec<Vec<NodeId>> = vec![Vec::new(); self.nodes.len()];
+        for (from, to) in self.edges() {
+            links[from].push(to);
+            links[to].push(from);
+        }
+
+        let mut seen = vec![false; self.nodes.len()];
+        let mut components = Vec::new();
+        for (id, _) in self.iter() {
+            if seen[id] {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue = VecDeque::new();
+            seen[id] = true;
+            queue.push_back(id);
+            while let Some(node) = queue.pop_front() {
+                component.push(node);
+                for next in &links[node] {
+                    if !seen[*next] {
+                        seen[*next] = true;
+                        queue.push_back(*next);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.components().len() <= 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 - 1 - 2 - 3 in a line with a shortcut from 0 to 3
+    fn square() -> Graph<()> {
+        let mut graph = Graph::new();
+        for _ in 0..4 {
+            graph.new_node(());
+        }
+        graph.connect(0, 1).unwrap();
+        graph.connect(1, 2).unwrap();
+        graph.connect(2, 3).unwrap();
+        graph.connect(0, 3).unwrap();
+        graph
+    }
+
+    #[test]
+    fn bfs_takes_fewest_hops() {
+        let graph = square();
+        assert_eq!(graph.shortest_path(0, 3), Some(vec![0, 3]));
+        // Both ways round are two hops, neighbors are tried in the order they were added
+        assert_eq!(graph.shortest_path(1, 3), Some(vec![1, 0, 3]));
+        assert_eq!(graph.shortest_path(2, 2), Some(vec![2]));
+        assert_eq!(graph.distances(0), vec![Some(0), Some(1), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn dijkstra_takes_cheaper_long_way() {
+        let graph = square();
+        // The shortcut costs more than going all the way round
+        let cost = |a : NodeId, b : NodeId| if (a, b) == (0, 3) || (a, b) == (3, 0) { 10 } else { 1 };
+        assert_eq!(graph.shortest_path_by(0, 3, cost), Some(vec![0, 1, 2, 3]));
+        assert_eq!(graph.shortest_path_by(0, 3, |_, _| 1), Some(vec![0, 3]));
+    }
+
+    #[test]
+    fn unreachable_is_none() {
+        let mut graph = square();
+        let lonely = graph.new_node(());
+        assert_eq!(graph.shortest_path(0, lonely), None);
+        assert_eq!(graph.shortest_path_by(0, lonely, |_, _| 1), None);
+        assert_eq!(graph.distances(0)[lonely], None);
+        assert_eq!(graph.nearest(0, |id, _| id == lonely), None);
+        assert!(!graph.is_reachable(0, lonely));
+
+        // Edges only go one way unless connected
+        graph.add_neighbor(lonely, 0).unwrap();
+        assert!(graph.is_reachable(lonely, 0));
+        assert!(!graph.is_reachable(0, lonely));
+    }
+
+    #[test]
+    fn remove_node_takes_its_edges() {
+        let mut graph = square();
+        assert_eq!(graph.remove_node(3), Some(()));
+        assert!(!graph.contains(3));
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.len(), 4);
+        assert!(graph.edges().all(|(from, to)| from != 3 && to != 3));
+        assert_eq!(graph.get_neighbors(0), Some(&vec![1]));
+        assert_eq!(graph.get_neighbors(2), Some(&vec![1]));
+        assert_eq!(graph.remove_node(3), None);
+    }
+
+    #[test]
+    fn connect_and_disconnect_dont_double_up() {
+        let mut graph = square();
+        graph.connect(0, 1).unwrap();
+        graph.add_neighbor(1, 0).unwrap();
+        assert_eq!(graph.get_neighbors(0), Some(&vec![1, 3]));
+        assert_eq!(graph.get_neighbors(1), Some(&vec![0, 2]));
+        assert_eq!(graph.edges().count(), 8);
+        assert!(graph.connect(0, 0).is_err());
+
+        assert!(graph.disconnect(0, 1));
+        assert!(!graph.has_edge(0, 1) && !graph.has_edge(1, 0));
+        assert!(!graph.disconnect(0, 1));
+        assert_eq!(graph.edges().count(), 6);
+    }
+
+    #[test]
+    fn components_finds_both_pieces() {
+        let mut graph = square();
+        let a = graph.new_node(());
+        let b = graph.new_node(());
+        // One way is enough to count as joined up
+        graph.add_neighbor(b, a).unwrap();
+
+        let mut components = graph.components();
+        for component in &mut components {
+            component.sort();
+        }
+        assert_eq!(components, vec![vec![0, 1, 2, 3], vec![a, b]]);
+        assert!(!graph.is_connected());
+
+        graph.connect(3, a).unwrap();
+        assert!(graph.is_connected());
+    }
+}
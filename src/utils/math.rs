@@ -66,3 +66,43 @@ impl<T: Add<Output=T>> Add for Vec3<T> {
         }
     }
 }
+
+// Steps between two cells without diagonals
+pub fn distance(a : Vec2<usize>, b : Vec2<usize>) -> usize {
+    ((a.x as i32 - b.x as i32).abs() + (a.y as i32 - b.y as i32).abs()) as usize
+}
+
+// Bresenham line from start to end, both ends included
+pub fn line(start : Vec2<usize>, end : Vec2<usize>) -> Vec<Vec2<usize>> {
+    let mut points = Vec::new();
+
+    let mut x = start.x as i32;
+    let mut y = start.y as i32;
+    let end_x = end.x as i32;
+    let end_y = end.y as i32;
+
+    let dx = (end_x - x).abs();
+    let dy = -(end_y - y).abs();
+    let step_x = if x < end_x { 1 } else { -1 };
+    let step_y = if y < end_y { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        points.push(Vec2::new(x as usize, y as usize));
+        if x == end_x && y == end_y {
+            break;
+        }
+
+        let err2 = 2 * err;
+        if err2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if err2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+
+    points
+}
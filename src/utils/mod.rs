@@ -1,5 +1,7 @@
 mod math;
 mod graph;
+mod rng;
 
-pub use self::math::{Vec2, Vec3};
-pub use self::graph::{Graph, Node};
\ No newline at end of file
+pub use self::math::{Vec2, Vec3, line, distance};
+pub use self::graph::{Graph, Node};
+pub use self::rng::{rng, seed};
\ No newline at end of file
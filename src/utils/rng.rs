@@ -0,0 +1,43 @@
+extern crate rand;
+
+use std::cell::RefCell;
+use self::rand::prelude::*;
+use self::rand::Error;
+
+// Everything random goes through here instead of thread_rng so
+// a whole dungeon can be built again from its seed
+thread_local! {
+    static GAME_RNG : RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub struct GameRng;
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        GAME_RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        GAME_RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest : &mut [u8]) {
+        GAME_RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest : &mut [u8]) -> Result<(), Error> {
+        GAME_RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+pub fn rng() -> GameRng {
+    GameRng
+}
+
+pub fn seed(seed : u64) {
+    let mut bytes = [0; 32];
+    for i in 0..8 {
+        bytes[i] = (seed >> (i * 8)) as u8;
+    }
+    GAME_RNG.with(|rng| *rng.borrow_mut() = StdRng::from_seed(bytes));
+}
@@ -2,17 +2,33 @@ extern crate rand;
 
 use utils::Graph;
 use utils::Vec2;
-use entity::{EntityMap, CorpseMap};
+use entity::{EntityMap, CorpseMap, Entity};
+use item::FloorItem;
 use player::Player;
 use log::Log;
+use spell::Spell;
 use world::Room;
 use world::Direction;
 use world::TileType;
 use world::DungeonBuilder;
+use world::ThemeKind;
+use world::{to_dot, to_ascii};
+use world::{FloorStats, validate_floor};
+use world::PrefabLibrary;
 
 use self::rand::prelude::*;
+use utils::rng;
 
-// RNG properties
+// Most turns a room gets caught up on when the player comes back
+const MAX_CATCH_UP : usize = 1000;
+
+// Extra ways between rooms on top of what's needed to reach them all
+const LOOPS : usize = 3;
+
+// Smallest room the carving and terrain can work with, walls included
+const MIN_ROOM_SIZE : usize = 5;
+
+// RNG properties. Every range includes both ends
 pub struct DungeonProperties {
     min_grid_size : Vec2<usize>,
     max_grid_size : Vec2<usize>,
@@ -21,7 +37,69 @@ pub struct DungeonProperties {
     min_depth : usize,
     max_depth : usize,
     min_rooms : usize,
-    max_rooms : usize
+    max_rooms : usize,
+    loops : usize
+}
+
+impl DungeonProperties {
+    pub fn new(depth : usize) -> DungeonProperties {
+        DungeonProperties {
+            min_grid_size : Vec2::new(10, 10),
+            max_grid_size : Vec2::new(10, 10),
+            min_room_size : Vec2::new(5, 5),
+            max_room_size : Vec2::new(15, 15),
+            min_depth : depth,
+            max_depth : depth,
+            min_rooms : 10,
+            max_rooms : 10,
+            loops : LOOPS
+        }
+    }
+
+    pub fn with_grid_size(mut self, min : Vec2<usize>, max : Vec2<usize>) -> DungeonProperties {
+        self.min_grid_size = min;
+        self.max_grid_size = max;
+        self
+    }
+
+    pub fn with_room_size(mut self, min : Vec2<usize>, max : Vec2<usize>) -> DungeonProperties {
+        self.min_room_size = min;
+        self.max_room_size = max;
+        self
+    }
+
+    pub fn with_depth(mut self, min : usize, max : usize) -> DungeonProperties {
+        self.min_depth = min;
+        self.max_depth = max;
+        self
+    }
+
+    pub fn with_rooms(mut self, min : usize, max : usize) -> DungeonProperties {
+        self.min_rooms = min;
+        self.max_rooms = max;
+        self
+    }
+
+    pub fn with_loops(mut self, loops : usize) -> DungeonProperties {
+        self.loops = loops;
+        self
+    }
+}
+
+fn roll(min : usize, max : usize, what : &str) -> Result<usize, String> {
+    if min > max {
+        return Err(format!("Min {} is over max {} for {}", min, max, what));
+    }
+    Ok(rng().gen_range(min, max + 1))
+}
+
+// Monster on its way through an exit after the player
+struct Follower {
+    monster : Box<Entity>,
+    floor : usize,
+    room : usize,
+    entrance : Vec2<usize>,
+    turns : usize
 }
 
 pub struct Dungeon {
@@ -30,30 +108,79 @@ pub struct Dungeon {
     active_room : usize,
     entrance_floor : usize,
     entrance_room : usize,
-    floors : Vec<Graph<Room>>
+    // Where the way down is on the entrance floor
+    exit_room : usize,
+    floors : Vec<Graph<Room>>,
+    // Per floor, which rooms the player has been in
+    visited : Vec<Vec<bool>>,
+    followers : Vec<Follower>,
+    turn : usize
 }
 
 impl Dungeon {
     pub fn new(depth : usize) -> Dungeon {
+        match Dungeon::generate(&DungeonProperties::new(depth)) {
+            Ok(dungeon) => dungeon,
+            Err(e) => panic!("Failed to build dungeon: {}", e)
+        }
+    }
 
-        let start = Vec2::new( rand::thread_rng().gen_range(0, 10),
-                            rand::thread_rng().gen_range(0, 10));
+    pub fn generate(properties : &DungeonProperties) -> Result<Dungeon, String> {
+        let depth = roll(properties.min_depth, properties.max_depth, "depth")?;
+        let rooms = roll(properties.min_rooms, properties.max_rooms, "rooms")?;
+        let size = Vec2::new(roll(properties.min_grid_size.x, properties.max_grid_size.x, "grid width")?,
+                             roll(properties.min_grid_size.y, properties.max_grid_size.y, "grid height")?);
+        if size.x == 0 || size.y == 0 {
+            return Err(String::from("Grid has no cells"));
+        }
+        let (min_room, max_room) = (properties.min_room_size, properties.max_room_size);
+        if min_room.x > max_room.x || min_room.y > max_room.y {
+            return Err(format!("Min room size {:?} is over max {:?}", min_room, max_room));
+        }
+        if min_room.x < MIN_ROOM_SIZE || min_room.y < MIN_ROOM_SIZE {
+            return Err(format!("Rooms have to be at least {}x{}, got {:?}", MIN_ROOM_SIZE, MIN_ROOM_SIZE, min_room));
+        }
+        let start = Vec2::new(rng().gen_range(0, size.x), rng().gen_range(0, size.y));
+
+        // Each floor picks a theme and its rooms use it
+        let theme = ThemeKind::get_theme(rng().gen_range(0, ThemeKind::count()));
+
+        let prefabs = PrefabLibrary::for_game().map_err(|e| format!("Failed to load prefabs: {}", e))?;
 
         let mut floors = Vec::new();
+        let mut layouts = Vec::new();
         floors.push(Graph::new());
         {
-            let mut grid = DungeonBuilder::new(start, Vec2::new(10,10), 10, &mut floors[0]);
-            grid.build_floor();
+            let mut grid = DungeonBuilder::new(start, size, rooms, depth, theme, &prefabs, &mut floors[0])
+                                .with_room_size(properties.min_room_size, properties.max_room_size)
+                                .with_loops(properties.loops);
+            layouts.push(grid.build_floor()?);
+        }
+        let mut visited = Vec::new();
+        for floor in &floors {
+            visited.push(vec![false; floor.len()]);
         }
 
-        Dungeon {
+        let mut dungeon = Dungeon {
             depth,
-            active_room : 0,
+            active_room : layouts[0].entrance,
             active_floor : 0,
             entrance_floor : 0,
-            entrance_room : 0,
-            floors
-        }
+            entrance_room : layouts[0].entrance,
+            exit_room : layouts[0].exit,
+            floors,
+            visited,
+            followers : Vec::new(),
+            turn : 0
+        };
+
+        // Whatever is in sight from the start is already explored
+        let start = dungeon.starting_position();
+        let (floorid, roomid) = (dungeon.entrance_floor, dungeon.entrance_room);
+        dungeon.visited[floorid][roomid] = true;
+        dungeon.get_mut_room(floorid, roomid).explore_from(start);
+
+        Ok(dungeon)
     }
 
     // Decide how you want to handle invalid active floor
@@ -86,6 +213,39 @@ impl Dungeon {
         self.active_room
     }
 
+    pub fn exit_room_id(&self) -> usize {
+        self.exit_room
+    }
+
+    // For designers and bug reports, see world::export
+    pub fn floor_dot(&self) -> String {
+        to_dot(&self.floors[self.active_floor])
+    }
+
+    pub fn floor_ascii(&self) -> String {
+        to_ascii(&self.floors[self.active_floor])
+    }
+
+    pub fn floor_stats(&self) -> FloorStats {
+        FloorStats::new(&self.floors[self.active_floor])
+    }
+
+    // Checks the floor the way the generator does, then goes through
+    // every exit like the player would
+    pub fn validate(&self) -> Result<(), String> {
+        let floor = &self.floors[self.active_floor];
+        validate_floor(floor, self.entrance_room)?;
+
+        for (_, node) in floor.iter() {
+            for (to, mut direction) in node.data.exits() {
+                direction.invert();
+                self.get_room(self.active_floor, to).entering_position(direction);
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn starting_position(&self) -> Vec2<usize> {
         let room = self.get_room(self.entrance_floor, self.entrance_room);
         room.initial_position()
@@ -96,11 +256,126 @@ impl Dungeon {
         room.valid_position(pos)
     }
 
+    pub fn room_size(&self) -> Vec2<usize> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        Vec2::new(room.width(), room.height())
+    }
+
+    pub fn movement_cost(&self, pos : Vec2<usize>) -> usize {
+        let room = self.get_room(self.active_floor, self.active_room);
+        room.movement_cost(pos)
+    }
+
+    pub fn field_of_view(&self, origin : Vec2<usize>) -> Vec<bool> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        room.field_of_view(origin)
+    }
+
+    pub fn projectile_path(&self, start : Vec2<usize>, end : Vec2<usize>, range : usize) -> Vec<Vec2<usize>> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        room.projectile_path(start, end, range)
+    }
+
+    pub fn fire_projectile(&mut self, 
+                           player : &mut Player, 
+                           end : Vec2<usize>, 
+                           spell : Option<Spell>,
+                           log : &mut Log) -> Vec<Vec2<usize>> 
+    {
+        let floorid = self.active_floor;
+        let roomid = self.active_room;
+        self.get_mut_room(floorid, roomid).fire_projectile(player, end, spell, log)
+    }
+
+    pub fn find_path(&self, start : Vec2<usize>, end : Vec2<usize>) -> Option<Vec<Vec2<usize>>> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        room.find_path(start, end)
+    }
+
+    pub fn teleport(&mut self, player : &mut Player) -> bool {
+        let floorid = self.active_floor;
+        let roomid = self.active_room;
+        self.get_mut_room(floorid, roomid).teleport(player)
+    }
+
+    // Every room on the floor, not just this one
+    pub fn reveal_traps(&mut self) -> usize {
+        let floorid = self.active_floor;
+        let mut count = 0;
+        for roomid in 0..self.floors[floorid].len() {
+            count += self.get_mut_room(floorid, roomid).reveal_traps();
+        }
+        count
+    }
+
+    // Every room on the floor counts as seen. Rooms the player hasn't
+    // been in only get caught up on from now, not from when they were built
+    pub fn map_floor(&mut self) {
+        let floorid = self.active_floor;
+        let turn = self.turn;
+        for roomid in 0..self.floors[floorid].len() {
+            if !self.visited[floorid][roomid] {
+                self.visited[floorid][roomid] = true;
+                self.get_mut_room(floorid, roomid).set_last_update(turn);
+            }
+            self.get_mut_room(floorid, roomid).explore_all();
+        }
+    }
+
+    pub fn close_door(&mut self, pos : Vec2<usize>) -> bool {
+        let floorid = self.active_floor;
+        let roomid = self.active_room;
+        self.get_mut_room(floorid, roomid).close_door(pos)
+    }
+
+    pub fn loot_corpse(&mut self, pos : Vec2<usize>, player : &mut Player, log : &mut Log) -> bool {
+        let floorid = self.active_floor;
+        let roomid = self.active_room;
+        self.get_mut_room(floorid, roomid).loot_corpse(pos, player, log)
+    }
+
     pub fn get_entities(&self) -> &EntityMap {
         let room = self.get_room(self.active_floor, self.active_room);
         room.get_entities()
     }
 
+    pub fn get_items(&self) -> &Vec<FloorItem> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        room.items()
+    }
+
+    pub fn visited(&self, roomid : usize) -> bool {
+        self.visited[self.active_floor][roomid]
+    }
+
+    pub fn fully_visited(&self) -> bool {
+        self.visited[self.active_floor].iter().all(|v| *v)
+    }
+
+    // Next tile auto explore should head to. Finish off this room 
+    // first, then head out the exit towards the closest room we
+    // haven't been in. None once there is nowhere left to go
+    pub fn explore_step(&self, pos : Vec2<usize>) -> Option<Vec2<usize>> {
+        let room = self.get_room(self.active_floor, self.active_room);
+        if let Some(path) = room.explore_path(pos) {
+            return path.first().cloned();
+        }
+
+        let next_room = self.next_room_to_explore()?;
+        let exit = room.exit_to(next_room)?;
+        room.find_path(pos, exit)?.first().cloned()
+    }
+
+    // Returns the neighbor of the active room that 
+    // leads to the closest unvisited room
+    fn next_room_to_explore(&self) -> Option<usize> {
+        let floor = &self.floors[self.active_floor];
+        let path = floor.nearest(self.active_room, |roomid, _| !self.visited(roomid))?;
+
+        // The active room itself still needs exploring
+        Some(*path.get(1).unwrap_or(&self.active_room))
+    }
+
     pub fn get_corpses(&self) -> &CorpseMap {
         let room = self.get_room(self.active_floor, self.active_room);
         room.get_corpses()
@@ -113,11 +388,51 @@ impl Dungeon {
         room.get_mut_entities()
     }
 
-    pub fn step(&mut self, player : &mut Player) {
+    pub fn step(&mut self, player : &mut Player, log : &mut Log) {
         let floorid = self.active_floor;
         let roomid = self.active_room;
-        let room = self.get_mut_room(floorid, roomid);
-        room.step(player);
+        self.turn += 1;
+        let turn = self.turn;
+        {
+            let room = self.get_mut_room(floorid, roomid);
+            room.step(player);
+            room.set_last_update(turn);
+        }
+
+        self.step_followers(player, log);
+    }
+
+    // Followers turn up at the exit they were chasing the player 
+    // through. If it's blocked they wait until there's space
+    fn step_followers(&mut self, player : &Player, log : &mut Log) {
+        let followers : Vec<Follower> = self.followers.drain(..).collect();
+        for mut follower in followers {
+            // The step right after the player goes through doesn't count
+            if follower.turns > 0 {
+                follower.turns -= 1;
+                self.followers.push(follower);
+                continue;
+            }
+
+            let in_sight = follower.floor == self.active_floor && follower.room == self.active_room;
+            let name = String::from(follower.monster.name());
+            let result = {
+                // The player's position only means anything in their own room
+                let player = if in_sight { Some(player) } else { None };
+                let room = self.get_mut_room(follower.floor, follower.room);
+                room.add_entity(follower.monster, follower.entrance, player)
+            };
+
+            match result {
+                Ok(_) => if in_sight {
+                    log.add_message(&format!("You are followed in by {}", name));
+                },
+                Err(monster) => {
+                    follower.monster = monster;
+                    self.followers.push(follower);
+                }
+            }
+        }
     }
 
     pub fn handle_player_input( &mut self, 
@@ -136,10 +451,57 @@ impl Dungeon {
             let mut entering_direction = exiting_direction;
             entering_direction.invert();
             
+            // Whatever was right behind the player gives chase
+            let exit_pos = *player.position();
+            let chasing = self.get_mut_room(floorid, roomid).take_followers(exit_pos);
+
             self.active_room = node_id;
             let new_pos = self.get_room(floorid, node_id).entering_position(entering_direction);
 
+            // Rooms only change while the player is around, make up 
+            // for the time away. Unvisited ones are still as built
+            let turn = self.turn;
+            let returning = self.visited[floorid][node_id];
+            self.visited[floorid][node_id] = true;
+            {
+                let room = self.get_mut_room(floorid, node_id);
+                if returning {
+                    let elapsed = (turn - room.last_update()).min(MAX_CATCH_UP);
+                    room.catch_up(elapsed, new_pos);
+                }
+                room.set_last_update(turn);
+            }
+
+            // Monster uuids only mean something inside a room
+            player.clear_target();
             player.move_player(new_pos);
+
+            for monster in chasing {
+                self.followers.push(Follower {
+                    monster,
+                    floor : floorid,
+                    room : node_id,
+                    entrance : new_pos,
+                    turns : rng().gen_range(1, 3)
+                });
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_rooms_are_rejected() {
+        let tiny = DungeonProperties::new(1).with_room_size(Vec2::new(2, 2), Vec2::new(2, 2));
+        assert!(Dungeon::generate(&tiny).is_err());
+
+        let narrow = DungeonProperties::new(1).with_room_size(Vec2::new(4, 8), Vec2::new(8, 8));
+        assert!(Dungeon::generate(&narrow).is_err());
+
+        let smallest = DungeonProperties::new(1).with_room_size(Vec2::new(5, 5), Vec2::new(5, 5));
+        assert!(Dungeon::generate(&smallest).is_ok());
+    }
+}
@@ -2,15 +2,20 @@ extern crate rand;
 
 use utils::Graph;
 use utils::Vec2;
-use world::Room;
+use world::{Room, RoomProperties};
 use world::Direction;
+use world::ThemeKind;
+use world::{Prefab, PrefabLibrary};
 
 use self::rand::prelude::*;
+use utils::rng;
+
+// 1 in PREFAB_CHANCE rooms try to be a prefab
+const PREFAB_CHANCE : usize = 5;
 
 // internal structures for managing room construction
 struct GridElement {
     pub has_room : bool,
-    pub filled : bool,
     pub node : usize,
     pub left_corner : Vec2<usize>
 }
@@ -19,29 +24,51 @@ impl GridElement {
     pub fn new(left_corner : Vec2<usize>) -> GridElement {
         GridElement{
             has_room : false,
-            filled : false,
             node : 0,
             left_corner
         }
     }
 }
 
+// Where a floor gets entered from and where its way down is
+pub struct FloorLayout {
+    pub entrance : usize,
+    pub exit : usize
+}
+
 pub struct DungeonBuilder<'a> {
     size : Vec2<usize>,
     num_rooms : usize,
     room_ct : usize,
+    // Connections on top of the ones needed to reach every room
+    loops : usize,
+    min_room_size : Vec2<usize>,
+    max_room_size : Vec2<usize>,
     start_cell : Vec2<usize>,
+    depth : usize,
+    theme : ThemeKind,
+    prefabs : &'a PrefabLibrary,
     grid : Vec<GridElement>,
+    // Cells in the order they got a room
+    cells : Vec<Vec2<usize>>,
+    // A cell and the direction of the cell it's joined to
+    links : Vec<(Vec2<usize>, Direction)>,
     graph : &'a mut Graph<Room>
 }
 
 impl<'a> DungeonBuilder<'a> {
-    // Enforce start is within bounds
-    pub fn new(start : Vec2<usize>, size : Vec2<usize>, num_rooms : usize, graph : &'a mut Graph<Room>) -> DungeonBuilder {
+    pub fn new(start : Vec2<usize>, 
+               size : Vec2<usize>, 
+               num_rooms : usize, 
+               depth : usize,
+               theme : ThemeKind,
+               prefabs : &'a PrefabLibrary,
+               graph : &'a mut Graph<Room>) -> DungeonBuilder<'a> 
+    {
         let mut grid = Vec::new();
         for i in 0..size.x*size.y {
             let x = i % size.x;
-            let y = i / size.y;
+            let y = i / size.x;
 
             grid.push(GridElement::new(Vec2::new(x,y)));
         }
@@ -50,12 +77,32 @@ impl<'a> DungeonBuilder<'a> {
             size,
             num_rooms : num_rooms,
             room_ct : 0,
+            loops : 0,
+            min_room_size : Vec2::new(5, 5),
+            max_room_size : Vec2::new(15, 15),
             start_cell : start,
+            depth,
+            theme,
+            prefabs,
             grid,
+            cells : Vec::new(),
+            links : Vec::new(),
             graph
         }
     }
 
+    pub fn with_loops(mut self, loops : usize) -> DungeonBuilder<'a> {
+        self.loops = loops;
+        self
+    }
+
+    // Both ends included
+    pub fn with_room_size(mut self, min : Vec2<usize>, max : Vec2<usize>) -> DungeonBuilder<'a> {
+        self.min_room_size = min;
+        self.max_room_size = max;
+        self
+    }
+
     fn get_cell(&self, location : Vec2<usize>) -> &GridElement {
         &self.grid[location.x + location.y * self.size.x]
     }
@@ -65,114 +112,315 @@ impl<'a> DungeonBuilder<'a> {
     }
 
     fn get_rand_direction(&self) -> Direction {
-        Direction::get_direction(rand::thread_rng().gen_range(0, 4))
+        Direction::get_direction(rng().gen_range(1, 5))
     }
 
-    fn build_room(&mut self, location : Vec2<usize>) {
-
-        if self.room_ct == self.num_rooms ||
-           location.x >= self.size.x || 
-           location.y >= self.size.y 
-        {
-            return;
+    // The next cell over, as long as it's still on the grid
+    fn step(&self, location : Vec2<usize>, direction : Direction) -> Option<Vec2<usize>> {
+        let next = direction.try_apply(location)?;
+        if next.x < self.size.x && next.y < self.size.y {
+            return Some(next);
         }
+        None
+    }
 
-        if !self.get_cell(location).has_room {
-            self.get_cell_mut(location).has_room = true;
-            self.room_ct += 1;
+    fn has_room(&self, location : Vec2<usize>, direction : Direction) -> bool {
+        match self.step(location, direction) {
+            Some(next) => self.get_cell(next).has_room,
+            None => false
         }
-        
-        let mut direction = self.get_rand_direction();
+    }
 
-        let mut stop = false;
-        let mut iterations = 0;
+    fn place_room(&mut self, location : Vec2<usize>) {
+        self.get_cell_mut(location).has_room = true;
+        self.cells.push(location);
+        self.room_ct += 1;
+    }
 
-        while !stop && iterations < 4 {
-            let res = direction.try_apply(location);
+    fn linked(&self, location : Vec2<usize>, direction : Direction) -> bool {
+        let other = match self.step(location, direction) {
+            Some(other) => other,
+            None => return false
+        };
+        let mut back = direction;
+        back.invert();
 
-            if let Some(next_cell) = res {
-                self.build_room(next_cell);
-            }
+        self.links.iter().any(|&(cell, d)| {
+            (cell == location && d == direction) || (cell == other && d == back)
+        })
+    }
+
+    // Grow out from rooms that are already down, a random one with
+    // space next to it each time. Only stops early once the grid is full
+    fn build_rooms(&mut self) -> Result<(), String> {
+        let start = self.start_cell;
+        self.place_room(start);
 
-            if self.room_ct == self.num_rooms {
-                stop = true;
+        while self.room_ct < self.num_rooms {
+            let open : Vec<Vec2<usize>> = self.cells.iter()
+                .cloned()
+                .filter(|cell| {
+                    let mut direction = Direction::North;
+                    (0..4).any(|_| {
+                        let free = match self.step(*cell, direction) {
+                            Some(next) => !self.get_cell(next).has_room,
+                            None => false
+                        };
+                        direction.rotate_cw();
+                        free
+                    })
+                })
+                .collect();
+
+            if open.is_empty() {
+                return Err(format!("Only fit {} of {} rooms", self.room_ct, self.num_rooms));
             }
-            iterations += 1;
-            direction.rotate_cw();
-        }   
-    }
-
-    fn populate_graph(&mut self, location : Vec2<usize>) -> usize {
-        let mut id = self.get_cell(location).node;
-        // Has a room and the room hasnt been built yet
-        if self.get_cell(location).has_room && 
-           !self.get_cell(location).filled 
-        {
-            let mut direction = Direction::North;
-
-            let rng_size = Vec2::new(rand::thread_rng().gen_range(5, 16),
-                                      rand::thread_rng().gen_range(5, 16));
-            id = self.graph.new_node(Room::new(rng_size));
-            
-            self.get_cell_mut(location).filled = true;
-            self.get_cell_mut(location).node = id;
 
-            let mut stop = false;
-            while !stop {
-                let res = direction.try_apply(location);
-                if let Some(next_cell) = res {
-                    if next_cell.x < self.size.x &&
-                       next_cell.y < self.size.y &&
-                       self.get_cell(next_cell).has_room 
-                    {
-                        let neighbor = self.populate_graph(next_cell);
-                        self.graph.add_neighbor(id, neighbor).unwrap();
-                        
-                        {
-                            let mut node = self.graph.get_mut(id).unwrap();
-                            node.data.add_neighbor( direction, neighbor );
-                        }
+            let location = open[rng().gen_range(0, open.len())];
+            let mut direction = self.get_rand_direction();
+            for _ in 0..4 {
+                if let Some(next) = self.step(location, direction) {
+                    if !self.get_cell(next).has_room {
+                        self.place_room(next);
+                        self.links.push((location, direction));
+                        break;
                     }
                 }
-
                 direction.rotate_cw();
-                if direction == Direction::North {
-                    stop = true;
-                }
             }
-        }  
+        }
 
-        id
+        Ok(())
     }
 
-    fn debug_print(&self) {
-        let mut ct = 0;
-        for i in &self.grid {
-            if i.has_room {
-                if i.node < 10 {
-                    print!("0");
+    // Join up some rooms that sit next to each other but weren't
+    // joined while growing, so there's more than one way around
+    fn add_loops(&mut self) {
+        let mut candidates = Vec::new();
+        for cell in &self.cells {
+            for direction in &[Direction::East, Direction::South] {
+                if self.has_room(*cell, *direction) && !self.linked(*cell, *direction) {
+                    candidates.push((*cell, *direction));
                 }
-                print!("{} ", i.node);
             }
-            else {
-                print!("__ ");
+        }
+
+        rng().shuffle(&mut candidates);
+        candidates.truncate(self.loops);
+        self.links.extend(candidates);
+    }
+
+    // Every so often swap a random room for a prefab. The prefab has
+    // to have an exit spot for every neighbor the cell will get
+    fn choose_prefab(&self, location : Vec2<usize>) -> Option<&'a Prefab> {
+        let prefabs = self.prefabs;
+        if prefabs.len() == 0 || rng().gen_range(0, PREFAB_CHANCE) != 0 {
+            return None;
+        }
+
+        let mut needed = Vec::new();
+        let mut direction = Direction::North;
+        for _ in 0..4 {
+            if self.linked(location, direction) {
+                needed.push(direction);
             }
-            ct += 1;
-            if ct >= self.size.x {
-                println!("");
-                ct = 0;
+            direction.rotate_cw();
+        }
+
+        let candidates : Vec<&'a Prefab> = prefabs.iter()
+                            .filter(|p| needed.iter().all(|d| p.allows_exit(*d)))
+                            .collect();
+        let total : usize = candidates.iter().map(|p| p.weight()).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng().gen_range(0, total);
+        for prefab in candidates {
+            if roll < prefab.weight() {
+                return Some(prefab);
             }
+            roll -= prefab.weight();
+        }
+
+        None
+    }
+
+    // The entrance is as far from the start cell as it gets and the
+    // exit as far from the entrance. Rooms get harder the further
+    // they are from the entrance
+    fn find_distances(&self) -> (FloorLayout, Vec<usize>) {
+        let mut cells = Graph::new();
+        for cell in &self.cells {
+            cells.new_node(*cell);
+        }
+        for &(cell, direction) in &self.links {
+            let other = self.step(cell, direction).unwrap();
+            let a = self.cells.iter().position(|c| *c == cell).unwrap();
+            let b = self.cells.iter().position(|c| *c == other).unwrap();
+            cells.connect(a, b).unwrap();
+        }
+
+        let farthest = |distances : &Vec<Option<usize>>| {
+            (0..distances.len()).max_by_key(|i| distances[*i]).unwrap()
+        };
+
+        let entrance = farthest(&cells.distances(0));
+        let distances = cells.distances(entrance);
+        let exit = farthest(&distances);
+
+        let layout = FloorLayout { entrance, exit };
+        (layout, distances.iter().map(|d| d.unwrap()).collect())
+    }
+
+    fn populate_graph(&mut self, distances : &Vec<usize>) {
+        for i in 0..self.cells.len() {
+            let location = self.cells[i];
+            let room = match self.choose_prefab(location) {
+                Some(prefab) => Room::from_prefab(prefab, self.theme),
+                None => {
+                    let (min, max) = (self.min_room_size, self.max_room_size);
+                    let rng_size = Vec2::new(rng().gen_range(min.x, max.x + 1),
+                                              rng().gen_range(min.y, max.y + 1));
+                    let properties = RoomProperties::new(self.depth, distances[i]);
+                    Room::new(rng_size, self.theme, &properties)
+                }
+            };
+            let id = self.graph.new_node(room);
+            self.get_cell_mut(location).node = id;
         }
 
-        println!("start at {:?}",self.start_cell );
-        println!("room ct {}", self.room_ct );
+        for i in 0..self.links.len() {
+            let (location, direction) = self.links[i];
+            let other = self.step(location, direction).unwrap();
+            let a = self.get_cell(location).node;
+            let b = self.get_cell(other).node;
+            let mut back = direction;
+            back.invert();
+
+            self.graph.connect(a, b).unwrap();
+            self.graph.get_mut(a).unwrap().data.add_neighbor(direction, b);
+            self.graph.get_mut(b).unwrap().data.add_neighbor(back, a);
+        }
     }
 
-    pub fn build_floor(&mut self) {
+    // Every room asked for gets placed and joined up, anything
+    // short of that is an error
+    pub fn build_floor(&mut self) -> Result<FloorLayout, String> {
         let start = self.start_cell;
-        self.build_room(start);
-        self.populate_graph(start);
+        if self.num_rooms == 0 {
+            return Err(String::from("A floor needs at least one room"));
+        }
+        if start.x >= self.size.x || start.y >= self.size.y {
+            return Err(format!("Start {:?} is outside the {}x{} grid", start, self.size.x, self.size.y));
+        }
+        if self.graph.len() != 0 {
+            return Err(String::from("Floor already has rooms in it"));
+        }
+
+        self.build_rooms()?;
+        self.add_loops();
+        let (layout, distances) = self.find_distances();
+        let entrance = layout.entrance;
+        let exit = layout.exit;
+        self.populate_graph(&distances);
+
+        // Translate from cell order to room ids
+        let layout = FloorLayout {
+            entrance : self.get_cell(self.cells[entrance]).node,
+            exit : self.get_cell(self.cells[exit]).node
+        };
+        validate_floor(self.graph, layout.entrance)?;
+        Ok(layout)
+    }
+}
+
+// Every room can be walked to from the entrance and every exit
+// leads somewhere that has a way back
+pub fn validate_floor(floor : &Graph<Room>, entrance : usize) -> Result<(), String> {
+    if !floor.contains(entrance) {
+        return Err(format!("Entrance {} isn't a room", entrance));
+    }
+
+    let reached = floor.reachable(entrance).len();
+    if reached != floor.node_count() {
+        return Err(format!("Only {} of {} rooms can be reached", reached, floor.node_count()));
+    }
+
+    for (id, node) in floor.iter() {
+        let room = &node.data;
+        for &(to, direction) in &room.exits() {
+            if !floor.has_edge(id, to) {
+                return Err(format!("Room {} has an exit to {} that isn't in the graph", id, to));
+            }
+
+            let mut back = direction;
+            back.invert();
+            let other = match floor.get(to) {
+                Some(other) => &other.data,
+                None => return Err(format!("Room {} has an exit to missing room {}", id, to))
+            };
+            if !other.has_entrance(back) {
+                return Err(format!("Room {} has no entrance for the exit from {}", to, id));
+            }
+            if !other.exits().contains(&(id, back)) {
+                return Err(format!("Room {} has no way back to {}", to, id));
+            }
+        }
+
+        for neighbor in node.neighbors() {
+            if room.exit_to(*neighbor).is_none() {
+                return Err(format!("Room {} is joined to {} without an exit", id, neighbor));
+            }
+        }
+
+        if !room.entrances_connected() {
+            return Err(format!("Room {} has entrances that can't reach each other", id));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::seed;
+
+    fn build(num_rooms : usize, loops : usize, size : Vec2<usize>, prefabs : &PrefabLibrary)
+        -> (Graph<Room>, Result<FloorLayout, String>)
+    {
+        let mut floor = Graph::new();
+        let result = {
+            let start = Vec2::new(size.x / 2, size.y / 2);
+            let mut builder = DungeonBuilder::new(start, size, num_rooms, 1, ThemeKind::Cave, prefabs, &mut floor)
+                                .with_loops(loops);
+            builder.build_floor()
+        };
+        (floor, result)
+    }
+
+    #[test]
+    fn every_room_placed_and_reachable() {
+        let prefabs = PrefabLibrary::built_in().unwrap();
+        for s in 0..25 {
+            for &(num_rooms, loops) in &[(1, 0), (5, 0), (10, 3), (20, 10), (25, 50)] {
+                seed(s);
+                let (floor, result) = build(num_rooms, loops, Vec2::new(5, 5), &prefabs);
+                let layout = result.unwrap();
+
+                assert!(validate_floor(&floor, layout.entrance).is_ok(), "seed {} rooms {}", s, num_rooms);
+                assert_eq!(floor.node_count(), num_rooms);
+                assert!(floor.is_connected());
+            }
+        }
+    }
 
-        self.debug_print();
+    #[test]
+    fn too_many_rooms_is_an_error() {
+        let prefabs = PrefabLibrary::built_in().unwrap();
+        seed(0);
+        let (_, result) = build(26, 0, Vec2::new(5, 5), &prefabs);
+        assert!(result.is_err());
     }
 }
@@ -0,0 +1,286 @@
+use std::collections::VecDeque;
+use utils::{Graph, Vec2};
+use entity::Drawable;
+use world::{Room, Direction, TileType};
+
+// Columns between rooms in the ascii picture, rows get one less
+// since the room number sits above each room
+const GAP : usize = 4;
+
+fn direction_name(direction : Direction) -> &'static str {
+    match direction {
+        Direction::North => "north",
+        Direction::East => "east",
+        Direction::South => "south",
+        Direction::West => "west"
+    }
+}
+
+// Rooms as nodes, every exit as an edge. Two rooms joined up
+// show two edges, one for each side's exit
+pub fn to_dot(floor : &Graph<Room>) -> String {
+    let mut dot = String::from("digraph floor {\n");
+    for (id, node) in floor.iter() {
+        let room = &node.data;
+        dot.push_str(&format!("    {} [label=\"{}\\n{}x{}\\n{} monsters\"];\n",
+                              id, id, room.width(), room.height(), room.get_entities().len()));
+    }
+
+    for (id, node) in floor.iter() {
+        for (to, direction) in node.data.exits() {
+            dot.push_str(&format!("    {} -> {} [label=\"{}\"];\n", id, to, direction_name(direction)));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+// Rooms came off a grid so following the exits out from any room
+// puts every room it reaches back in its cell. Anything that can't
+// be reached goes in a row under the rest
+fn place_rooms(floor : &Graph<Room>) -> Vec<Option<(i32, i32)>> {
+    let mut cells : Vec<Option<(i32, i32)>> = vec![None; floor.len()];
+    let mut queue = VecDeque::new();
+
+    for (start, _) in floor.iter() {
+        if cells[start].is_some() {
+            continue;
+        }
+
+        let row = cells.iter().filter_map(|c| *c).map(|(_, y)| y + 2).max().unwrap_or(0);
+        cells[start] = Some((0, row));
+        queue.push_back(start);
+        while let Some(id) = queue.pop_front() {
+            let (x, y) = cells[id].unwrap();
+            for (to, direction) in floor.get(id).unwrap().data.exits() {
+                if !floor.contains(to) || cells[to].is_some() {
+                    continue;
+                }
+
+                let (dx, dy) = match direction {
+                    Direction::North => (0, -1),
+                    Direction::East => (1, 0),
+                    Direction::South => (0, 1),
+                    Direction::West => (-1, 0)
+                };
+                cells[to] = Some((x + dx, y + dy));
+                queue.push_back(to);
+            }
+        }
+    }
+
+    cells
+}
+
+fn room_icon(tile : &TileType) -> char {
+    match *tile {
+        TileType::Exit{..} => 'E',
+        _ => tile.value().icon
+    }
+}
+
+// Where each exit ended up on the canvas, by the room it leads to
+fn exit_positions(room : &Room, corner : Vec2<usize>) -> Vec<(usize, Direction, Vec2<usize>)> {
+    room.exits().iter()
+                .map(|&(to, direction)| {
+                    let pos = room.exit_to(to).unwrap();
+                    (to, direction, Vec2::new(corner.x + pos.x, corner.y + pos.y))
+                })
+                .collect()
+}
+
+// Straight out of one exit, across and into the other
+fn draw_corridor(canvas : &mut Vec<Vec<char>>, from : Vec2<usize>, to : Vec2<usize>, direction : Direction) {
+    match direction {
+        Direction::East => {
+            let mid = (from.x + to.x) / 2;
+            for x in from.x+1..mid+1 {
+                canvas[from.y][x] = '-';
+            }
+            for y in from.y.min(to.y)..from.y.max(to.y)+1 {
+                canvas[y][mid] = '|';
+            }
+            for x in mid+1..to.x {
+                canvas[to.y][x] = '-';
+            }
+        },
+        Direction::South => {
+            let mid = (from.y + to.y) / 2;
+            for y in from.y+1..mid+1 {
+                canvas[y][from.x] = '|';
+            }
+            for x in from.x.min(to.x)..from.x.max(to.x)+1 {
+                canvas[mid][x] = '-';
+            }
+            for y in mid+1..to.y {
+                canvas[y][to.x] = '|';
+            }
+        },
+        _ => {}
+    }
+}
+
+// Every room laid out where it sits on the floor, with its tiles,
+// monsters and items, and corridors standing in for the exits
+pub fn to_ascii(floor : &Graph<Room>) -> String {
+    let cells = place_rooms(floor);
+    let placed : Vec<(usize, (i32, i32))> = cells.iter()
+                                                 .enumerate()
+                                                 .filter_map(|(id, c)| c.map(|c| (id, c)))
+                                                 .collect();
+    if placed.is_empty() {
+        return String::new();
+    }
+
+    let min_x = placed.iter().map(|&(_, (x, _))| x).min().unwrap();
+    let min_y = placed.iter().map(|&(_, (_, y))| y).min().unwrap();
+    let max_x = placed.iter().map(|&(_, (x, _))| x).max().unwrap();
+    let max_y = placed.iter().map(|&(_, (_, y))| y).max().unwrap();
+    let cell_w = floor.iter().map(|(_, n)| n.data.width()).max().unwrap() + GAP;
+    let cell_h = floor.iter().map(|(_, n)| n.data.height()).max().unwrap() + GAP;
+
+    let columns = (max_x - min_x + 1) as usize * cell_w;
+    let rows = (max_y - min_y + 1) as usize * cell_h;
+    let mut canvas = vec![vec![' '; columns]; rows];
+
+    // Top left of every room on the canvas, under its number
+    let mut corners = vec![None; floor.len()];
+    for &(id, (x, y)) in &placed {
+        corners[id] = Some(Vec2::new((x - min_x) as usize * cell_w,
+                                     (y - min_y) as usize * cell_h + 1));
+    }
+
+    for &(id, _) in &placed {
+        let room = &floor.get(id).unwrap().data;
+        let corner = corners[id].unwrap();
+
+        for (i, tile) in room.tiles().iter().enumerate() {
+            canvas[corner.y + i / room.width()][corner.x + i % room.width()] = room_icon(&tile.id);
+        }
+        for item in room.items() {
+            let pos = item.draw().position;
+            canvas[corner.y + pos.y][corner.x + pos.x] = item.draw().icon;
+        }
+        for e in room.get_entities().values() {
+            let pos = e.draw().position;
+            canvas[corner.y + pos.y][corner.x + pos.x] = e.draw().icon;
+        }
+    }
+
+    // Each pair only needs drawing once, from its west or north side
+    for &(id, _) in &placed {
+        let room = &floor.get(id).unwrap().data;
+        for (to, direction, from) in exit_positions(room, corners[id].unwrap()) {
+            if direction != Direction::East && direction != Direction::South {
+                continue;
+            }
+
+            let other = match floor.get(to) {
+                Some(other) => &other.data,
+                None => continue
+            };
+            let end = match (corners[to], other.exit_to(id)) {
+                (Some(corner), Some(pos)) => Vec2::new(corner.x + pos.x, corner.y + pos.y),
+                _ => continue
+            };
+            let ahead = match direction {
+                Direction::East => end.x > from.x + 1,
+                _ => end.y > from.y + 1
+            };
+            if ahead {
+                draw_corridor(&mut canvas, from, end, direction);
+            }
+        }
+    }
+
+    for &(id, _) in &placed {
+        let corner = corners[id].unwrap();
+        for (i, c) in id.to_string().chars().enumerate() {
+            canvas[corner.y - 1][corner.x + i] = c;
+        }
+    }
+
+    let mut ascii = String::new();
+    for row in canvas {
+        let line : String = row.into_iter().collect();
+        ascii.push_str(line.trim_end());
+        ascii.push('\n');
+    }
+
+    ascii
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::{Prefab, ThemeKind};
+
+    // Three rooms in an L, the first one joined to both others
+    fn small_floor() -> Graph<Room> {
+        let maps = [
+            "name = Start\n---\n#####\n#.g.E\n#...#\n#...#\n##E##",
+            "name = East\n---\n#####\n#...#\nE...#\n#...#\n#####",
+            "name = South\n---\n#E###\n#...#\n#.g.#\n#...#\n#####",
+        ];
+
+        let mut floor = Graph::new();
+        for map in maps.iter() {
+            let prefab = Prefab::parse(map).unwrap();
+            floor.new_node(Room::from_prefab(&prefab, ThemeKind::Cave));
+        }
+
+        for &(a, b, direction) in &[(0, 1, Direction::East), (0, 2, Direction::South)] {
+            let mut back = direction;
+            back.invert();
+            floor.connect(a, b).unwrap();
+            floor.get_mut(a).unwrap().data.add_neighbor(direction, b);
+            floor.get_mut(b).unwrap().data.add_neighbor(back, a);
+        }
+
+        floor
+    }
+
+    #[test]
+    fn dot_lists_every_room_and_exit() {
+        let expected = [
+            "digraph floor {",
+            "    0 [label=\"0\\n5x5\\n1 monsters\"];",
+            "    1 [label=\"1\\n5x5\\n0 monsters\"];",
+            "    2 [label=\"2\\n5x5\\n1 monsters\"];",
+            "    0 -> 1 [label=\"east\"];",
+            "    0 -> 2 [label=\"south\"];",
+            "    1 -> 0 [label=\"west\"];",
+            "    2 -> 0 [label=\"north\"];",
+            "}",
+            "",
+        ];
+        assert_eq!(to_dot(&small_floor()), expected.join("\n"));
+    }
+
+    #[test]
+    fn ascii_lays_rooms_out_on_the_grid() {
+        let expected = [
+            "0        1",
+            "#####    #####",
+            "#.g.E-|  #...#",
+            "#...# |--E...#",
+            "#...#    #...#",
+            "##E##    #####",
+            "  |",
+            " --",
+            " |",
+            "2|",
+            "#E###",
+            "#...#",
+            "#.g.#",
+            "#...#",
+            "#####",
+            "",
+            "",
+            "",
+            "",
+        ];
+        assert_eq!(to_ascii(&small_floor()), expected.join("\n"));
+    }
+}
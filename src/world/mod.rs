@@ -2,12 +2,22 @@ mod world;
 mod dungeon;
 mod dungeon_builder;
 mod room;
+mod room_shape;
+mod prefab;
 mod tile;
 mod direction;
+mod theme;
+mod export;
+mod stats;
 
 pub use self::world::{World, WorldNode};
 pub use self::direction::Direction;
-pub use self::dungeon::Dungeon;
-pub use self::dungeon_builder::DungeonBuilder;
-pub use self::room::Room;
-pub use self::tile::{Tile, TileType, TileDisplay};
\ No newline at end of file
+pub use self::dungeon::{Dungeon, DungeonProperties};
+pub use self::dungeon_builder::{DungeonBuilder, validate_floor};
+pub use self::room::{Room, RoomProperties};
+pub use self::room_shape::RoomShape;
+pub use self::prefab::{Prefab, PrefabCell, PrefabLibrary};
+pub use self::tile::{Tile, TileType, TileDisplay, TileEffect};
+pub use self::theme::{ThemeKind, ThemeSet};
+pub use self::export::{to_dot, to_ascii};
+pub use self::stats::FloorStats;
\ No newline at end of file
@@ -0,0 +1,330 @@
+use std::fs;
+use std::env;
+use std::path::Path;
+
+use utils::Vec2;
+use world::Direction;
+use world::room::is_monster_glyph;
+
+// Hand made rooms loaded from the text files in assets/prefabs.
+// A file is a small header and then the map after a "---" line
+//
+//   name = Treasure Vault
+//   weight = 2
+//   ---
+//   ###E###
+//   #..$..#
+//   E.g.g.E
+//   ###E###
+//
+// Map legend
+//   ' ' rock, '#' wall, '.' floor, '+' door, '~' deep water, 
+//   '=' lava, ':' rubble, '^' hidden trap, '$' loot spot,
+//   'E' exit allowed here (edges only), lowercase letters are
+//   monsters standing on floor ('g' goblin, 'a' archer, 's' shaman,
+//   'j' slime, 't' troll, 'b' bat)
+
+// Built into the binary the same way the themes are so the game
+// doesn't need the source checkout around to find them
+const BUILT_IN : [(&str, &str); 3] = [
+    ("goblin_warren.txt", include_str!("../assets/prefabs/goblin_warren.txt")),
+    ("lava_shrine.txt", include_str!("../assets/prefabs/lava_shrine.txt")),
+    ("treasure_vault.txt", include_str!("../assets/prefabs/treasure_vault.txt")),
+];
+
+// Point this at a folder of prefab files to try them out without
+// rebuilding. They replace the built in ones
+pub const PREFAB_DIR_VAR : &str = "RUSTY_ROGUE_PREFABS";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PrefabCell {
+    Rock,
+    Wall,
+    Floor,
+    Door,
+    DeepWater,
+    Lava,
+    Rubble,
+    Trap,
+    Loot,
+    Exit,
+    Monster(char)
+}
+
+impl PrefabCell {
+    fn from_char(c : char) -> Option<PrefabCell> {
+        match c {
+            ' ' => Some(PrefabCell::Rock),
+            '#' => Some(PrefabCell::Wall),
+            '.' => Some(PrefabCell::Floor),
+            '+' => Some(PrefabCell::Door),
+            '~' => Some(PrefabCell::DeepWater),
+            '=' => Some(PrefabCell::Lava),
+            ':' => Some(PrefabCell::Rubble),
+            '^' => Some(PrefabCell::Trap),
+            '$' => Some(PrefabCell::Loot),
+            'E' => Some(PrefabCell::Exit),
+            _ if is_monster_glyph(c) => Some(PrefabCell::Monster(c)),
+            _ => None
+        }
+    }
+
+    fn open_ground(&self) -> bool {
+        match *self {
+            PrefabCell::Rock | PrefabCell::Wall | PrefabCell::Exit => false,
+            _ => true
+        }
+    }
+}
+
+pub struct Prefab {
+    name : String,
+    weight : usize,
+    size : Vec2<usize>,
+    cells : Vec<PrefabCell>
+}
+
+impl Prefab {
+    pub fn parse(contents : &str) -> Result<Prefab, String> {
+        let mut name = String::new();
+        let mut weight = 1;
+        let mut lines = contents.lines();
+
+        // Header
+        loop {
+            let line = match lines.next() {
+                Some(line) => line.trim(),
+                None => return Err(String::from("Missing --- before the map")),
+            };
+
+            if line == "---" {
+                break;
+            }
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts.next().unwrap_or("").trim();
+            match key {
+                "name" => name = String::from(value),
+                "weight" => weight = match value.parse() {
+                    Ok(weight) => weight,
+                    Err(_) => return Err(format!("Invalid weight {}", value)),
+                },
+                _ => return Err(format!("Unknown header {}", key)),
+            }
+        }
+
+        // Map. Editors like to strip trailing spaces so pad
+        // short rows out with rock
+        let rows : Vec<&str> = lines.map(|l| l.trim_end())
+                                    .filter(|l| !l.is_empty())
+                                    .collect();
+        let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let height = rows.len();
+        if width < 5 || height < 5 {
+            return Err(String::from("Map has to be at least 5x5"));
+        }
+
+        let mut cells = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let mut chars = row.chars();
+            for x in 0..width {
+                let c = chars.next().unwrap_or(' ');
+                let cell = match PrefabCell::from_char(c) {
+                    Some(cell) => cell,
+                    None => return Err(format!("Unknown map character '{}' at {},{}", c, x, y)),
+                };
+
+                let edge = x == 0 || y == 0 || x == width-1 || y == height-1;
+                let corner = (x == 0 || x == width-1) && (y == 0 || y == height-1);
+                if edge && cell.open_ground() {
+                    return Err(format!("Open ground on the edge at {},{}", x, y));
+                }
+                if cell == PrefabCell::Exit && (!edge || corner) {
+                    return Err(format!("Exits have to be on an edge, found one at {},{}", x, y));
+                }
+                cells.push(cell);
+            }
+        }
+
+        if name.is_empty() {
+            return Err(String::from("Missing name"));
+        }
+
+        let prefab = Prefab {
+            name,
+            weight,
+            size : Vec2::new(width, height),
+            cells
+        };
+
+        prefab.validate()?;
+        Ok(prefab)
+    }
+
+    // Every exit has to lead into the room and all the open 
+    // ground has to be one piece
+    fn validate(&self) -> Result<(), String> {
+        let mut open = Vec::new();
+        for i in 0..self.cells.len() {
+            let pos = Vec2::new(i % self.size.x, i / self.size.x);
+            if self.cells[i].open_ground() {
+                open.push(pos);
+            }
+            else if self.cells[i] == PrefabCell::Exit {
+                let inward = self.exit_direction(pos)
+                                 .map(|mut d| { d.invert(); d })
+                                 .and_then(|d| d.try_apply(pos));
+                match inward {
+                    Some(inside) if self.cell(inside).open_ground() => {},
+                    _ => return Err(format!("Exit at {},{} doesnt lead anywhere", pos.x, pos.y)),
+                }
+            }
+        }
+
+        if open.is_empty() {
+            return Err(String::from("Map has no floor"));
+        }
+
+        let mut reached = vec![false; self.cells.len()];
+        let mut stack = vec![open[0]];
+        reached[open[0].x + open[0].y * self.size.x] = true;
+        while let Some(pos) = stack.pop() {
+            let mut direction = Direction::North;
+            for _ in 0..4 {
+                if let Some(next) = direction.try_apply(pos) {
+                    let i = next.x + next.y * self.size.x;
+                    if next.x < self.size.x && next.y < self.size.y &&
+                       !reached[i] && self.cells[i].open_ground()
+                    {
+                        reached[i] = true;
+                        stack.push(next);
+                    }
+                }
+                direction.rotate_cw();
+            }
+        }
+
+        if open.iter().any(|p| !reached[p.x + p.y * self.size.x]) {
+            return Err(String::from("Map floor is split into more than one piece"));
+        }
+
+        Ok(())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn cell(&self, pos : Vec2<usize>) -> PrefabCell {
+        self.cells[pos.x + pos.y * self.size.x]
+    }
+
+    // Which wall an edge position sits on
+    pub fn exit_direction(&self, pos : Vec2<usize>) -> Option<Direction> {
+        if pos.y == 0 {
+            Some(Direction::North)
+        }
+        else if pos.x == self.size.x-1 {
+            Some(Direction::East)
+        }
+        else if pos.y == self.size.y-1 {
+            Some(Direction::South)
+        }
+        else if pos.x == 0 {
+            Some(Direction::West)
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn allows_exit(&self, direction : Direction) -> bool {
+        (0..self.cells.len()).any(|i| {
+            let pos = Vec2::new(i % self.size.x, i / self.size.x);
+            self.cells[i] == PrefabCell::Exit && 
+            self.exit_direction(pos) == Some(direction)
+        })
+    }
+}
+
+pub struct PrefabLibrary {
+    prefabs : Vec<Prefab>
+}
+
+impl PrefabLibrary {
+    pub fn new() -> PrefabLibrary {
+        PrefabLibrary {
+            prefabs : Vec::new()
+        }
+    }
+
+    pub fn built_in() -> Result<PrefabLibrary, String> {
+        let mut library = PrefabLibrary::new();
+        for &(name, contents) in BUILT_IN.iter() {
+            match Prefab::parse(contents) {
+                Ok(prefab) => library.prefabs.push(prefab),
+                Err(e) => return Err(format!("{}: {}", name, e)),
+            }
+        }
+
+        Ok(library)
+    }
+
+    // The folder in PREFAB_DIR_VAR if it's set, otherwise the built in ones
+    pub fn for_game() -> Result<PrefabLibrary, String> {
+        match env::var(PREFAB_DIR_VAR) {
+            Ok(dir) => PrefabLibrary::load(dir),
+            Err(_) => PrefabLibrary::built_in()
+        }
+    }
+
+    // Every .txt file in the folder is a prefab. A missing folder
+    // or a broken file is an error so designers find out about it
+    pub fn load<P : AsRef<Path>>(dir : P) -> Result<PrefabLibrary, String> {
+        let mut library = PrefabLibrary::new();
+        let dir = dir.as_ref();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => return Err(format!("{}: {}", dir.display(), e)),
+        };
+
+        let mut paths : Vec<_> = entries.filter_map(|e| e.ok())
+                                        .map(|e| e.path())
+                                        .filter(|p| p.extension().map_or(false, |ext| ext == "txt"))
+                                        .collect();
+        paths.sort();
+
+        for path in paths {
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) => return Err(format!("{}: {}", path.display(), e)),
+            };
+            match Prefab::parse(&contents) {
+                Ok(prefab) => library.prefabs.push(prefab),
+                Err(e) => return Err(format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        Ok(library)
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefabs.len()
+    }
+
+    pub fn iter(&self) -> ::std::slice::Iter<'_, Prefab> {
+        self.prefabs.iter()
+    }
+}
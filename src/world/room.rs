@@ -1,52 +1,151 @@
 extern crate rand;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::borrow::BorrowMut;
 use self::rand::prelude::*;
 
-use utils::Vec2;
-use world::{Tile, TileType};
-use entity::{EntityMap, Entity, CorpseMap, Corpse, Attack};
+use utils::{Vec2, line, distance, rng};
+use world::{Tile, TileType, TileEffect};
+use entity::{EntityMap, Entity, CorpseMap, Corpse, Attack, StatusEffect, StatusKind};
 use player::Player;
 use log::Log;
 use world::Direction;
+use world::ThemeKind;
+use world::RoomShape;
+use world::{Prefab, PrefabCell};
 
 // Entities
 use goblin::Goblin;
+use monster::{Archer, Shaman, Slime, Troll, Bat};
+use item::{Item, FloorItem, Consumable};
+use spell::Spell;
+
+// 1 in ITEM_CHANCE rooms have something lying around
+const ITEM_CHANCE : usize = 3;
+
+// At most one monster for every DENSITY tiles of open floor
+const DENSITY : usize = 8;
+
+// Catching up a room the player left behind. Monsters take a 
+// random step every WANDER_TURNS and something new can move
+// in every RESPAWN_TURNS
+const CORPSE_DECAY : usize = 500;
+const WANDER_TURNS : usize = 5;
+const MAX_WANDER : usize = 20;
+const RESPAWN_TURNS : usize = 200;
+const MAX_RESPAWNS : usize = 2;
+// Respawns keep this far from where the player comes in
+const RESPAWN_DISTANCE : usize = 4;
 
 struct Entrance {
     pub location : Vec2<usize>,
     pub direction : Direction
 }
 
+// How dangerous a room is. Monsters cost points out of the
+// budget, deeper floors and rooms further from the entrance
+// get more points and nastier things to spend them on
+#[derive(Clone)]
 pub struct RoomProperties {
-    // theme
-    // "Difficulty" 
-    //// possible monsters
-    //// amount of monsters
+    pub budget : usize,
+    // Monster glyph and how likely it is to be picked
+    pub pool : Vec<(char, usize)>,
+    // Most monsters the room can hold regardless of budget
+    pub density : usize
+}
+
+impl RoomProperties {
+    pub fn new(depth : usize, distance : usize) -> RoomProperties {
+        let danger = depth + distance;
+
+        let mut pool = vec![('g', 6), ('b', 3), ('a', 2)];
+        if danger >= 2 {
+            pool.push(('s', 2));
+            pool.push(('j', 2));
+        }
+        if danger >= 4 {
+            pool.push(('t', depth));
+        }
+
+        RoomProperties {
+            budget : depth + distance * 2,
+            pool,
+            density : DENSITY
+        }
+    }
+
+    // Picks something affordable, None once the budget can't buy anything
+    fn choose(&self, budget : usize) -> Option<char> {
+        let options : Vec<&(char, usize)> = self.pool.iter()
+                                                .filter(|&&(glyph, _)| monster_cost(glyph) <= budget)
+                                                .collect();
+        let total : usize = options.iter().map(|&&(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng().gen_range(0, total);
+        for &&(glyph, weight) in &options {
+            if roll < weight {
+                return Some(glyph);
+            }
+            roll -= weight;
+        }
+
+        None
+    }
 }
 
 pub struct Room {
     size : Vec2<usize>,
+    shape : RoomShape,
+    theme : ThemeKind,
     init_pos : Vec2<usize>,
     tiles : Vec<Tile>,
     entrances : Vec<Entrance>,
+    // Prefabs limit where exits can go. Empty means anywhere
+    exit_spots : Vec<Vec2<usize>>,
+    loot_spots : Vec<Vec2<usize>>,
+    // Tiles the player has had in sight
+    explored : Vec<bool>,
+    items : Vec<FloorItem>,
     entities : EntityMap,
-    corpses : CorpseMap
+    corpses : CorpseMap,
+    // What respawns get picked from. Prefabs never respawn
+    properties : Option<RoomProperties>,
+    // Dungeon turn this room was last stepped or caught up on
+    last_update : usize,
+    // Turns monsters still owe for crossing slow ground, 
+    // same as the extra steps the player pays
+    slowed : HashMap<usize, usize>,
+    // Monsters that went after the player on their last turn
+    chasing : HashSet<usize>
 }
 
 impl Room {
-    pub fn new(size : Vec2<usize>) -> Room {
-        let mut tiles = Vec::new();
+    pub fn new(size : Vec2<usize>, theme : ThemeKind, properties : &RoomProperties) -> Room {
+        let shape = RoomShape::random(size);
+        let floor = shape.carve(size);
 
+        let mut tiles = Vec::new();
         for i in 0..size.x*size.y {
-            let x = i % size.x;
-            let y = i / size.x;
-            if x != 0 && y != 0 && x != size.x-1 && y != size.y-1 {
+            if floor[i] {
                 tiles.push(Tile::new(TileType::Granite));
             }
             else {
-                tiles.push(Tile::new(TileType::Wall));
+                tiles.push(Tile::new(TileType::Rock));
+            }
+        }
+        build_walls(&mut tiles, size);
+
+        add_terrain(&mut tiles, size, shape);
+
+        // Start on the first bit of floor we find
+        let mut init_pos = Vec2::new(1,1);
+        for i in 0..tiles.len() {
+            if let TileType::Granite = tiles[i].id {
+                init_pos = Vec2::new(i % size.x, i / size.x);
+                break;
             }
         }
 
@@ -54,33 +153,124 @@ impl Room {
         let mut uuid = 0;
 
         let mut entities : EntityMap = HashMap::new();
-        // Max range should be based on area I think
-        let available_space = size.x * size.y - (size.y *2) - (size.x-2 * 2);
-        let num_gobbos = rand::thread_rng().gen_range(1, available_space/3);
-        for i in 0..num_gobbos {
-            let mut pos = Vec2::new(rand::thread_rng().gen_range(2, size.x-1),
-                                rand::thread_rng().gen_range(2, size.y-1));
-            
-            while tiles[pos.x + pos.y * size.x].occupied {
-                pos = Vec2::new(rand::thread_rng().gen_range(2, size.x-1),
-                                rand::thread_rng().gen_range(2, size.y-1));    
-            }
-
-            tiles[pos.x + pos.y * size.x].occupied = true;
-            tiles[pos.x + pos.y * size.x].uuid = uuid;
-
-            let bc = Box::new(Goblin::new(pos));
-            entities.insert(uuid, bc);
-            uuid += 1;
-        }  
+        let mut spots = free_floor(&tiles);
+        let cap = spots.len() / properties.density;
+        let mut budget = properties.budget;
+        while entities.len() < cap && !spots.is_empty() {
+            let glyph = match properties.choose(budget) {
+                Some(glyph) => glyph,
+                None => break
+            };
+            budget -= monster_cost(glyph);
+
+            let i = spots.swap_remove(rng().gen_range(0, spots.len()));
+            let pos = Vec2::new(i % size.x, i / size.x);
+            if let Some(monster) = spawn_monster(glyph, pos) {
+                tiles[i].occupied = true;
+                tiles[i].uuid = uuid;
+                entities.insert(uuid, monster);
+                uuid += 1;
+            }
+        }
+
+        let mut items = Vec::new();
+        if rng().gen_range(0, ITEM_CHANCE) == 0 {
+            let spots = free_floor(&tiles);
+            if !spots.is_empty() {
+                let i = spots[rng().gen_range(0, spots.len())];
+                let pos = Vec2::new(i % size.x, i / size.x);
+                items.push(FloorItem::new(pos, Item::Consumable(Consumable::random())));
+            }
+        }
+
+        Room {
+            size,
+            shape,
+            theme,
+            init_pos,
+            tiles,
+            entrances : Vec::new(),
+            exit_spots : Vec::new(),
+            loot_spots : Vec::new(),
+            explored : vec![false; size.x * size.y],
+            items,
+            entities,
+            corpses : HashMap::new(),
+            properties : Some(properties.clone()),
+            last_update : 0,
+            slowed : HashMap::new(),
+            chasing : HashSet::new()
+        }
+    }
+
+    pub fn from_prefab(prefab : &Prefab, theme : ThemeKind) -> Room {
+        let size = prefab.size();
+        let mut tiles = Vec::new();
+        let mut entities : EntityMap = HashMap::new();
+        let mut exit_spots = Vec::new();
+        let mut loot_spots = Vec::new();
+        let mut init_pos = None;
+        let mut uuid = 0;
+
+        for i in 0..size.x*size.y {
+            let pos = Vec2::new(i % size.x, i / size.x);
+            let tile_type = match prefab.cell(pos) {
+                PrefabCell::Rock => TileType::Rock,
+                PrefabCell::Wall => TileType::Wall,
+                PrefabCell::Door => TileType::Door{open : false},
+                PrefabCell::DeepWater => TileType::DeepWater,
+                PrefabCell::Lava => TileType::Lava,
+                PrefabCell::Rubble => TileType::Rubble,
+                PrefabCell::Trap => TileType::Trap{hidden : true},
+                PrefabCell::Exit => {
+                    exit_spots.push(pos);
+                    TileType::Wall
+                },
+                PrefabCell::Loot => {
+                    loot_spots.push(pos);
+                    TileType::Granite
+                },
+                PrefabCell::Floor => {
+                    if init_pos.is_none() {
+                        init_pos = Some(pos);
+                    }
+                    TileType::Granite
+                },
+                PrefabCell::Monster(_) => TileType::Granite,
+            };
+            tiles.push(Tile::new(tile_type));
+
+            if let PrefabCell::Monster(glyph) = prefab.cell(pos) {
+                if let Some(monster) = spawn_monster(glyph, pos) {
+                    tiles[i].occupied = true;
+                    tiles[i].uuid = uuid;
+                    entities.insert(uuid, monster);
+                    uuid += 1;
+                }
+            }
+        }
+
+        let items = loot_spots.iter()
+                              .map(|pos| FloorItem::new(*pos, Item::Consumable(Consumable::random())))
+                              .collect();
 
         Room {
             size,
-            init_pos : Vec2::new(1,1),
+            shape : RoomShape::Prefab,
+            theme,
+            init_pos : init_pos.unwrap_or(Vec2::new(1,1)),
             tiles,
             entrances : Vec::new(),
+            exit_spots,
+            loot_spots,
+            explored : vec![false; size.x * size.y],
+            items,
             entities,
-            corpses : HashMap::new()
+            corpses : HashMap::new(),
+            properties : None,
+            last_update : 0,
+            slowed : HashMap::new(),
+            chasing : HashSet::new()
         }
     }
 
@@ -101,52 +291,259 @@ impl Room {
         };
 
         let size = self.size;
-        let mut position : Vec2<usize>;
-        // I think I can shrink this by doing NORTH || SOUTH and using apply direction
-        match direction {
-            Direction::North => {
-                let rng = rand::thread_rng().gen_range(1, size.x-1);
-                position = Vec2::new(rng, 0);
-                self.get_tile_mut(position).id = exit_tile;
-                self.entrances.push(Entrance {
-                    location : Vec2::new(position.x, position.y+1),
-                    direction : direction
-                });
-            },
-            Direction::East => {
-                let rng = rand::thread_rng().gen_range(1, size.y-1);
-                position = Vec2::new(size.x-1, rng);
-                self.get_tile_mut(position).id = exit_tile;
-                self.entrances.push(Entrance {
-                    location : Vec2::new(position.x-1, position.y),
-                    direction : direction
-                });
-            },
-            Direction::South => {
-                let rng = rand::thread_rng().gen_range(1, size.x-1);
-                position = Vec2::new(rng, size.y-1);
-                self.get_tile_mut(position).id = exit_tile;
-                self.entrances.push(Entrance {
-                    location : Vec2::new(position.x, position.y-1),
-                    direction : direction
-                });
-            },
-            Direction::West => {
-                let rng = rand::thread_rng().gen_range(1, size.y-1);
-                position = Vec2::new(0, rng);
-                self.get_tile_mut(position).id = exit_tile;
-                self.entrances.push(Entrance {
-                    location : Vec2::new(position.x+1, position.y),
-                    direction : direction
-                });
+        let mut inward = direction;
+        inward.invert();
+
+        let mut candidates : Vec<Vec2<usize>> = match direction {
+            Direction::North => (1..size.x-1).map(|x| Vec2::new(x, 0)).collect(),
+            Direction::East => (1..size.y-1).map(|y| Vec2::new(size.x-1, y)).collect(),
+            Direction::South => (1..size.x-1).map(|x| Vec2::new(x, size.y-1)).collect(),
+            Direction::West => (1..size.y-1).map(|y| Vec2::new(0, y)).collect(),
+        };
+        if !self.exit_spots.is_empty() {
+            let exit_spots = &self.exit_spots;
+            candidates.retain(|c| exit_spots.contains(c));
+        }
+        rng().shuffle(&mut candidates);
+
+        // Prefer a spot that opens straight onto the floor
+        let mut position = None;
+        for candidate in &candidates {
+            let location = inward.try_apply(*candidate).unwrap();
+            if self.safe_entrance(location) {
+                position = Some(*candidate);
+                break;
+            }
+        }
+
+        // Otherwise dig in from the edge until we hit the room.
+        // Rooms are a single region so that keeps everything connected
+        if position.is_none() {
+            for candidate in &candidates {
+                if let Some(tunnel) = self.tunnel(*candidate, inward) {
+                    for loc in tunnel {
+                        self.get_tile_mut(loc).id = TileType::Granite;
+                    }
+                    position = Some(*candidate);
+                    break;
+                }
+            }
+        }
+
+        let position = position.expect("Room has no floor to put an exit on");
+        let location = inward.try_apply(position).unwrap();
+        if !self.safe_entrance(location) {
+            self.get_tile_mut(location).id = TileType::Granite;
+        }
+
+        self.get_tile_mut(position).id = exit_tile;
+        build_walls(&mut self.tiles, size);
+
+        self.entrances.push(Entrance {
+            location,
+            direction
+        });
+
+        debug_assert!(self.entrances_connected());
+    }
+
+    // Tiles between an edge and the first bit of open ground
+    fn tunnel(&self, start : Vec2<usize>, inward : Direction) -> Option<Vec<Vec2<usize>>> {
+        let mut tunnel = Vec::new();
+        let mut pos = start;
+        while let Some(next) = inward.try_apply(pos) {
+            if next.x == 0 || next.y == 0 || 
+               next.x >= self.size.x-1 || next.y >= self.size.y-1 
+            {
+                return None;
+            }
+
+            if open_ground(self.get_tile_type(next)) {
+                return Some(tunnel);
+            }
+
+            tunnel.push(next);
+            pos = next;
+        }
+
+        None
+    }
+
+    // Can every entrance walk to every other entrance.
+    // Doors count since the player can open them
+    pub fn entrances_connected(&self) -> bool {
+        if self.entrances.is_empty() {
+            return true;
+        }
+
+        let mut reached = vec![false; self.tiles.len()];
+        let mut queue = VecDeque::new();
+        let start = self.entrances[0].location;
+        reached[start.x + start.y * self.size.x] = true;
+        queue.push_back(start);
+        while let Some(pos) = queue.pop_front() {
+            let mut direction = Direction::North;
+            for _ in 0..4 {
+                if let Some(next) = direction.try_apply(pos) {
+                    let i = next.x + next.y * self.size.x;
+                    if next.x < self.size.x && next.y < self.size.y &&
+                       !reached[i] && open_ground(self.tiles[i].id)
+                    {
+                        reached[i] = true;
+                        queue.push_back(next);
+                    }
+                }
+                direction.rotate_cw();
+            }
+        }
+
+        self.entrances.iter().all(|e| reached[e.location.x + e.location.y * self.size.x])
+    }
+
+    // Shortest walk from start to end, not counting start.
+    // Closed doors are fine since walking into them opens them
+    pub fn find_path(&self, start : Vec2<usize>, end : Vec2<usize>) -> Option<Vec<Vec2<usize>>> {
+        if end.x >= self.size.x || end.y >= self.size.y || !self.travel_safe(end, true) {
+            return None;
+        }
+
+        self.search(start, |pos| pos == end)
+    }
+
+    // Path to the closest bit of the room that hasn't been seen yet
+    pub fn explore_path(&self, start : Vec2<usize>) -> Option<Vec<Vec2<usize>>> {
+        self.search(start, |pos| {
+            let i = pos.x + pos.y * self.size.x;
+            match self.tiles[i].id {
+                TileType::Exit{..} => false,
+                _ => !self.explored[i]
+            }
+        })
+    }
+
+    // Where the exit leading to neighbor_id is
+    pub fn exit_to(&self, neighbor_id : usize) -> Option<Vec2<usize>> {
+        for (i, tile) in self.tiles.iter().enumerate() {
+            if let TileType::Exit{node_id, ..} = tile.id {
+                if node_id == neighbor_id {
+                    return Some(Vec2::new(i % self.size.x, i / self.size.x));
+                }
+            }
+        }
+        None
+    }
+
+    // Which room every exit leads to and which way it faces
+    pub fn exits(&self) -> Vec<(usize, Direction)> {
+        self.tiles.iter()
+                  .filter_map(|tile| match tile.id {
+                      TileType::Exit{node_id, exiting_direction} => Some((node_id, exiting_direction)),
+                      _ => None
+                  })
+                  .collect()
+    }
+
+    pub fn has_entrance(&self, entering_direction : Direction) -> bool {
+        self.entrances.iter().any(|e| e.direction == entering_direction)
+    }
+
+    // Everything in sight from origin counts as explored
+    pub fn explore_from(&mut self, origin : Vec2<usize>) {
+        let visible = self.field_of_view(origin);
+        for (i, seen) in visible.iter().enumerate() {
+            if *seen {
+                self.explored[i] = true;
+            }
+        }
+    }
+
+    pub fn explore_all(&mut self) {
+        for explored in &mut self.explored {
+            *explored = true;
+        }
+    }
+
+    // Breadth first walk out from start to the closest tile is_goal likes
+    fn search<F>(&self, start : Vec2<usize>, is_goal : F) -> Option<Vec<Vec2<usize>>> 
+        where F : Fn(Vec2<usize>) -> bool
+    {
+        let mut came_from : Vec<Option<Vec2<usize>>> = vec![None; self.tiles.len()];
+        let mut queue = VecDeque::new();
+        let mut found = None;
+        came_from[start.x + start.y * self.size.x] = Some(start);
+        queue.push_back(start);
+        while let Some(pos) = queue.pop_front() {
+            if pos != start && is_goal(pos) {
+                found = Some(pos);
+                break;
+            }
+
+            let mut direction = Direction::North;
+            for _ in 0..4 {
+                if let Some(next) = direction.try_apply(pos) {
+                    if next.x < self.size.x && next.y < self.size.y {
+                        let i = next.x + next.y * self.size.x;
+                        if came_from[i].is_none() && self.travel_safe(next, is_goal(next)) {
+                            came_from[i] = Some(pos);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                direction.rotate_cw();
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut pos = found?;
+        while pos != start {
+            path.push(pos);
+            pos = came_from[pos.x + pos.y * self.size.x].unwrap();
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    // Travel steers around monsters and anything that hurts. 
+    // Exits are only walked through on purpose and hidden 
+    // traps look like floor
+    fn travel_safe(&self, pos : Vec2<usize>, allow_exit : bool) -> bool {
+        let tile = self.get_tile(pos);
+        if tile.occupied {
+            return false;
+        }
+
+        match tile.id {
+            TileType::Door{..} | TileType::Trap{hidden : true} => true,
+            TileType::Exit{..} => allow_exit,
+            _ => {
+                let properties = tile.id.properties();
+                match properties.on_enter {
+                    Some(TileEffect::Attack(..)) => false,
+                    _ => properties.passable
+                }
             }
         }
     }
 
+    // Don't drop the player into a wall or a pool of lava
+    fn safe_entrance(&self, location : Vec2<usize>) -> bool {
+        let properties = self.get_tile(location).id.properties();
+        properties.passable && properties.on_enter.is_none()
+    }
+
     pub fn tiles(&self) -> &Vec<Tile> {
         &self.tiles
     }
 
+    // Bounds checked lookup for anyone outside the room
+    pub fn tile(&self, pos : Vec2<usize>) -> Option<&Tile> {
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+        Some(self.get_tile(pos))
+    }
+
     pub fn width(&self) -> usize {
         self.size.x
     }
@@ -155,6 +552,18 @@ impl Room {
         self.size.y
     }
 
+    pub fn shape(&self) -> RoomShape {
+        self.shape
+    }
+
+    pub fn theme(&self) -> ThemeKind {
+        self.theme
+    }
+
+    pub fn loot_spots(&self) -> &Vec<Vec2<usize>> {
+        &self.loot_spots
+    }
+
     pub fn initial_position(&self) -> Vec2<usize> {
         self.init_pos
     }
@@ -163,7 +572,7 @@ impl Room {
         if pos.x < self.size.x && pos.y < self.size.y {
             let result = self.tiles.get(pos.x + pos.y * self.size.x);
             if let Some(Tile) = result {
-                return !Tile.id.collidable();
+                return Tile.id.properties().passable;
             }
             else {
                 return false;
@@ -175,6 +584,13 @@ impl Room {
 
     pub fn step(&mut self, player : &mut Player) {
         let mut rem = Vec::new();
+        let mut heals = Vec::new();
+        let mut spawned = Vec::new();
+        let wounded = self.entities.values()
+                          .filter(|m| m.alive() && m.current_stats().health < m.base_stats().health)
+                          .count();
+        let p_pos = *player.position();
+        let in_sight = self.field_of_view(p_pos);
 
         for (uuid, m) in &mut self.entities {
             if !m.alive() {
@@ -187,17 +603,67 @@ impl Room {
             }
             else {
                 let curr_pos = *m.position();
-                let mut res : Option<Attack>;
-                {
+
+                // Stunned/frozen monsters lose the turn but still burn
+                let mut act = m.status().can_act();
+                if let Some(owed) = self.slowed.get_mut(uuid) {
+                    if *owed > 0 {
+                        *owed -= 1;
+                        act = false;
+                    }
+                }
+                let damage = m.status_mut().tick();
+                if damage > 0 {
+                    m.receive_attack(&Attack::over_time(damage, curr_pos));
+                }
+
+                if m.alive() {
+                    m.regenerate();
+                }
+
+                let mut res : Option<Attack> = None;
+                if act && m.alive() {
                     let m_mut : &mut Entity = m.borrow_mut();
                     res = m_mut.update(player, &self.tiles, self.size); 
                 }
+                // Seeing the player and going for them is what makes
+                // a monster follow them out of the room
+                if act && m.alive() {
+                    let closer = distance(*m.position(), p_pos) < distance(curr_pos, p_pos);
+                    if in_sight[curr_pos.x + curr_pos.y * self.size.x] && (res.is_some() || closer) {
+                        self.chasing.insert(*uuid);
+                    }
+                    else {
+                        self.chasing.remove(uuid);
+                    }
+                }
                 if let Some(attack) = res {
                     player.receive_attack(&attack);
                 }
+
+                // Don't waste a heal when only the healer is hurt
+                let hurt = m.current_stats().health < m.base_stats().health;
+                if act && m.alive() && wounded > hurt as usize {
+                    if let Some((amount, range)) = m.heal_allies() {
+                        heals.push((*uuid, *m.position(), amount, range));
+                    }
+                }
+                if let Some(pos) = open_neighbor(&self.tiles, self.size, *m.position(), player) {
+                    if let Some(child) = m.split(pos) {
+                        self.tiles[pos.x + pos.y * self.size.x].occupied = true;
+                        spawned.push(child);
+                    }
+                }
                 
                 // Do this either way. In case I want move+attack action?
                 let new_pos = *m.position();
+                if new_pos != curr_pos {
+                    let properties = self.tiles[new_pos.x + new_pos.y * self.size.x].id.properties();
+                    self.slowed.insert(*uuid, properties.movement_cost - 1);
+                    if properties.on_enter == Some(TileEffect::Extinguish) {
+                        m.status_mut().remove(StatusKind::Burning);
+                    }
+                }
                 self.tiles[curr_pos.x + curr_pos.y * self.size.x].occupied = false;
                 self.tiles[new_pos.x + new_pos.y * self.size.x].occupied = true;
                 self.tiles[new_pos.x + new_pos.y * self.size.x].uuid = *uuid;
@@ -206,13 +672,231 @@ impl Room {
         }
         
         for uuid in &rem {
-            let pos = *self.entities.get(uuid).unwrap().position();
-            self.corpses.insert(*uuid, Corpse::new(pos));
+            self.bury(*uuid);
+        }
+        self.decay_corpses(1);
+
+        for (healer, pos, amount, range) in heals {
+            for (uuid, m) in &mut self.entities {
+                if *uuid != healer && m.alive() && distance(pos, *m.position()) <= range {
+                    m.heal(amount);
+                }
+            }
+        }
+
+        for child in spawned {
+            let uuid = self.next_uuid();
+            let pos = *child.position();
+            self.get_tile_mut(pos).uuid = uuid;
+            self.entities.insert(uuid, child);
+        }
 
-            self.get_tile_mut(pos).occupied = false;
-            self.get_tile_mut(pos).corpses.push(*uuid);
-            self.entities.remove(uuid);
+        self.explore_from(*player.position());
+    }
+
+    // Swaps a dead monster for its corpse
+    fn bury(&mut self, uuid : usize) {
+        let pos = *self.entities.get(&uuid).unwrap().position();
+        self.corpses.insert(uuid, Corpse::new(pos));
+
+        self.get_tile_mut(pos).occupied = false;
+        self.get_tile_mut(pos).corpses.push(uuid);
+        self.entities.remove(&uuid);
+        self.slowed.remove(&uuid);
+        self.chasing.remove(&uuid);
+    }
+
+    fn decay_corpses(&mut self, turns : usize) {
+        for corpse in self.corpses.values_mut() {
+            corpse.grow_older(turns);
+        }
+
+        let rotten : Vec<usize> = self.corpses.iter()
+                                      .filter(|&(_, c)| c.age() >= CORPSE_DECAY)
+                                      .map(|(uuid, _)| *uuid)
+                                      .collect();
+        for uuid in rotten {
+            let pos = *self.corpses.remove(&uuid).unwrap().position();
+            self.get_tile_mut(pos).corpses.retain(|id| *id != uuid);
+        }
+    }
+
+    pub fn last_update(&self) -> usize {
+        self.last_update
+    }
+
+    pub fn set_last_update(&mut self, turn : usize) {
+        self.last_update = turn;
+    }
+
+    // A rough version of turns worth of step for a room nobody 
+    // was watching. No fighting, monsters just heal up, wander 
+    // about and new ones move in. entrance is where the player 
+    // is about to come in
+    pub fn catch_up(&mut self, turns : usize, entrance : Vec2<usize>) {
+        let uuids : Vec<usize> = self.entities.keys().cloned().collect();
+        let mut dead = Vec::new();
+        for uuid in &uuids {
+            let m = self.entities.get_mut(uuid).unwrap();
+            for _ in 0..turns {
+                let damage = m.status_mut().tick();
+                if damage > 0 {
+                    let pos = *m.position();
+                    m.receive_attack(&Attack::over_time(damage, pos));
+                }
+                if !m.alive() {
+                    dead.push(*uuid);
+                    break;
+                }
+                m.regenerate();
+            }
+        }
+        for uuid in dead {
+            self.bury(uuid);
+        }
+
+        let wander = (turns / WANDER_TURNS).min(MAX_WANDER);
+        for uuid in self.entities.keys().cloned().collect::<Vec<usize>>() {
+            let mut moves = 0;
+            while moves < wander {
+                moves += self.wander(uuid, entrance);
+            }
+        }
+
+        self.decay_corpses(turns);
+        self.respawn((turns / RESPAWN_TURNS).min(MAX_RESPAWNS), entrance);
+    }
+
+    // One random step onto somewhere a monster would walk,
+    // staying off the tile the player is about to land on.
+    // Gives back how many steps that took
+    fn wander(&mut self, uuid : usize, entrance : Vec2<usize>) -> usize {
+        let pos = *self.entities.get(&uuid).unwrap().position();
+        let mut options = Vec::new();
+        let mut direction = Direction::North;
+        for _ in 0..4 {
+            if let Some(next) = direction.try_apply(pos) {
+                if next.x < self.size.x && next.y < self.size.y && 
+                   next != entrance && self.get_tile(next).walkable() 
+                {
+                    options.push(next);
+                }
+            }
+            direction.rotate_cw();
+        }
+        if options.is_empty() {
+            return 1;
+        }
+
+        let next = options[rng().gen_range(0, options.len())];
+        self.entities.get_mut(&uuid).unwrap().set_position(next);
+        self.get_tile_mut(pos).occupied = false;
+        self.get_tile_mut(next).occupied = true;
+        self.get_tile_mut(next).uuid = uuid;
+        self.movement_cost(next)
+    }
+
+    // Spends the room's budget again, up to count monsters
+    fn respawn(&mut self, count : usize, entrance : Vec2<usize>) {
+        let properties = match self.properties {
+            Some(ref properties) => properties.clone(),
+            None => return
+        };
+
+        let mut spots : Vec<usize> = free_floor(&self.tiles).into_iter()
+                            .filter(|i| {
+                                let pos = Vec2::new(i % self.size.x, i / self.size.x);
+                                distance(pos, entrance) >= RESPAWN_DISTANCE
+                            })
+                            .collect();
+        let cap = (free_floor(&self.tiles).len() + self.entities.len()) / properties.density;
+
+        for _ in 0..count {
+            if self.entities.len() >= cap || spots.is_empty() {
+                return;
+            }
+
+            let glyph = match properties.choose(properties.budget) {
+                Some(glyph) => glyph,
+                None => return
+            };
+            let i = spots.swap_remove(rng().gen_range(0, spots.len()));
+            let pos = Vec2::new(i % self.size.x, i / self.size.x);
+            if let Some(monster) = spawn_monster(glyph, pos) {
+                let uuid = self.next_uuid();
+                self.tiles[i].occupied = true;
+                self.tiles[i].uuid = uuid;
+                self.entities.insert(uuid, monster);
+            }
+        }
+    }
+
+    // Monsters right next to pos that were chasing the player and
+    // are able to keep at it. They're taken out of the room completely
+    pub fn take_followers(&mut self, pos : Vec2<usize>) -> Vec<Box<Entity>> {
+        let uuids : Vec<usize> = self.entities.iter()
+                                     .filter(|&(uuid, _)| self.chasing.contains(uuid))
+                                     .filter(|&(_, m)| m.alive() && distance(*m.position(), pos) == 1)
+                                     .filter(|&(_, m)| !m.status().has(StatusKind::Stunned) && 
+                                                       !m.status().has(StatusKind::Frozen))
+                                     .map(|(uuid, _)| *uuid)
+                                     .collect();
+
+        let mut followers = Vec::new();
+        for uuid in uuids {
+            let monster = self.entities.remove(&uuid).unwrap();
+            self.slowed.remove(&uuid);
+            self.chasing.remove(&uuid);
+            self.get_tile_mut(*monster.position()).occupied = false;
+            followers.push(monster);
         }
+
+        followers
+    }
+
+    // Drops a monster on the closest free tile to near, keeping off
+    // the player if they're in this room. Hands it back if the room 
+    // is too full to fit it
+    pub fn add_entity(&mut self, monster : Box<Entity>, near : Vec2<usize>, 
+                      player : Option<&Player>) -> Result<(), Box<Entity>> 
+    {
+        let pos = {
+            let tiles = &self.tiles;
+            let size = self.size;
+            // Keep the way back out clear
+            let free = |pos : Vec2<usize>| {
+                let tile = &tiles[pos.x + pos.y * size.x];
+                let exit = match tile.id {
+                    TileType::Exit{..} => true,
+                    _ => false
+                };
+                tile.walkable() && !exit && !player.map_or(false, |p| p.collision(pos))
+            };
+            if free(near) {
+                near
+            }
+            else {
+                match self.search(near, free).and_then(|path| path.last().cloned()) {
+                    Some(pos) => pos,
+                    None => return Err(monster)
+                }
+            }
+        };
+
+        let mut monster = monster;
+        monster.set_position(pos);
+        let uuid = self.next_uuid();
+        self.get_tile_mut(pos).occupied = true;
+        self.get_tile_mut(pos).uuid = uuid;
+        self.entities.insert(uuid, monster);
+        Ok(())
+    }
+
+    // Corpses keep the uuid of what died so skip those too
+    fn next_uuid(&self) -> usize {
+        let entities = self.entities.keys().max().map_or(0, |id| id + 1);
+        let corpses = self.corpses.keys().max().map_or(0, |id| id + 1);
+        entities.max(corpses)
     }
 
     pub fn get_entities(&self) -> &EntityMap {
@@ -245,14 +929,207 @@ impl Room {
             }
         }
 
-        if !blocked && self.valid_position(new_pos) {
-            player.move_player(new_pos);
+        if !blocked {
+            if let TileType::Door{open : false} = self.get_tile_type(new_pos) {
+                self.get_tile_mut(new_pos).id = TileType::Door{open : true};
+                log.add_message("You open the door");
+            }
+            else if self.valid_position(new_pos) {
+                player.move_player(new_pos);
+                self.enter_tile(player, log);
+                self.pick_up(player, log);
+            }
         }
 
         self.get_tile_type(new_pos)
     }
 
-    fn get_tile(&mut self, loc : Vec2<usize>) -> &Tile {
+    fn enter_tile(&mut self, player : &mut Player, log : &mut Log) {
+        let pos = *player.position();
+        let tile_type = self.get_tile_type(pos);
+        match tile_type.properties().on_enter {
+            Some(TileEffect::Attack(attack_type, damage)) => {
+                if let TileType::Trap{hidden : true} = tile_type {
+                    self.get_tile_mut(pos).id = TileType::Trap{hidden : false};
+                    log.add_message("You triggered a trap!");
+                }
+                // Trap darts are poisoned
+                if let TileType::Trap{..} = tile_type {
+                    player.status_mut().apply(StatusEffect::new(StatusKind::Poisoned, 5, 1));
+                }
+                let attack = Attack::new(attack_type, damage, pos);
+                let result = player.receive_attack(&attack);
+                log.add_message(&format!("The {} hits you for {}", 
+                                         self.get_tile_type(pos).name(), 
+                                         result.dmg_dealt));
+            },
+            Some(TileEffect::Extinguish) => {
+                if player.status().has(StatusKind::Burning) {
+                    player.status_mut().remove(StatusKind::Burning);
+                    log.add_message("The water puts out the flames");
+                }
+            },
+            None => {}
+        }
+    }
+
+    // Where a shot from start towards end actually goes. It stops short
+    // of anything solid and at the first entity it runs into
+    pub fn projectile_path(&self, start : Vec2<usize>, end : Vec2<usize>, range : usize) -> Vec<Vec2<usize>> {
+        let mut path = Vec::new();
+        for p in line(start, end).into_iter().skip(1).take(range) {
+            if !self.get_tile(p).id.properties().passable {
+                break;
+            }
+
+            path.push(p);
+            if self.entities.values().any(|e| e.collision(p)) {
+                break;
+            }
+        }
+
+        path
+    }
+
+    // Returns the path the shot took so it can be drawn.
+    // No spell means an arrow or whatever else is thrown
+    pub fn fire_projectile(&mut self, 
+                           player : &mut Player, 
+                           end : Vec2<usize>, 
+                           spell : Option<Spell>,
+                           log : &mut Log) -> Vec<Vec2<usize>> 
+    {
+        let path = self.projectile_path(*player.position(), end, player.range());
+
+        let mut hit = false;
+        if let Some(last) = path.last() {
+            for (uuid, m) in &mut self.entities {
+                if m.collision(*last) {
+                    let attack = match spell {
+                        Some(spell) => spell.attack(player, *last),
+                        None => player.send_ranged_attack(*last)
+                    };
+                    let result = m.receive_attack(&attack);
+                    log.log_combat(&player, &result);
+                    player.set_target(*uuid);
+                    hit = true;
+                }
+            }
+        }
+
+        if !hit {
+            log.add_message("Your shot hits nothing");
+        }
+
+        path
+    }
+
+    // Everything on the tile goes in the pack
+    fn pick_up(&mut self, player : &mut Player, log : &mut Log) {
+        let pos = *player.position();
+        let (here, rest) : (Vec<FloorItem>, Vec<FloorItem>) = self.items.drain(..)
+                                                                  .partition(|i| *i.position() == pos);
+        self.items = rest;
+
+        for floor_item in here {
+            let item = floor_item.take();
+            log.add_message(&format!("You pick up the {}", item.name()));
+            player.add_item(item);
+        }
+    }
+
+    // Searching a corpse only ever works once. Something is
+    // found about half the time
+    pub fn loot_corpse(&mut self, pos : Vec2<usize>, player : &mut Player, log : &mut Log) -> bool {
+        let uuid = match self.corpses.iter().find(|&(_, c)| *c.position() == pos && !c.looted()) {
+            Some((uuid, _)) => *uuid,
+            None => return false
+        };
+        self.corpses.get_mut(&uuid).unwrap().set_looted();
+
+        if rng().gen_range(0, 2) == 0 {
+            log.add_message("You find nothing");
+            return true;
+        }
+
+        let item = Item::Consumable(Consumable::random());
+        log.add_message(&format!("You find a {}", item.name()));
+        player.add_item(item);
+        true
+    }
+
+    pub fn items(&self) -> &Vec<FloorItem> {
+        &self.items
+    }
+
+    // Drops the player somewhere random that's safe to stand
+    pub fn teleport(&mut self, player : &mut Player) -> bool {
+        let spots = free_floor(&self.tiles);
+        if spots.is_empty() {
+            return false;
+        }
+
+        let i = spots[rng().gen_range(0, spots.len())];
+        player.set_position(i % self.size.x, i / self.size.x);
+        true
+    }
+
+    pub fn reveal_traps(&mut self) -> usize {
+        let mut count = 0;
+        for tile in &mut self.tiles {
+            if let TileType::Trap{hidden : true} = tile.id {
+                tile.id = TileType::Trap{hidden : false};
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    // Closes any open door next to pos that isn't blocked
+    pub fn close_door(&mut self, pos : Vec2<usize>) -> bool {
+        let mut closed = false;
+        let mut direction = Direction::North;
+        for _ in 0..4 {
+            if let Some(loc) = direction.try_apply(pos) {
+                if loc.x < self.size.x && loc.y < self.size.y {
+                    let tile = self.get_tile_mut(loc);
+                    if let TileType::Door{open : true} = tile.id {
+                        if !tile.occupied && tile.corpses.is_empty() {
+                            tile.id = TileType::Door{open : false};
+                            closed = true;
+                        }
+                    }
+                }
+            }
+            direction.rotate_cw();
+        }
+
+        closed
+    }
+
+    pub fn movement_cost(&self, pos : Vec2<usize>) -> usize {
+        self.get_tile(pos).id.properties().movement_cost
+    }
+
+    // A tile is visible if the line to it doesnt pass through 
+    // anything opaque. The opaque tile itself is still visible
+    pub fn field_of_view(&self, origin : Vec2<usize>) -> Vec<bool> {
+        let mut visible = vec![false; self.tiles.len()];
+        for i in 0..self.tiles.len() {
+            let target = Vec2::new(i % self.size.x, i / self.size.x);
+            let points = line(origin, target);
+            let blocked = points.iter()
+                                .skip(1)
+                                .take(points.len().saturating_sub(2))
+                                .any(|p| self.get_tile(*p).id.properties().opaque);
+            visible[i] = !blocked;
+        }
+
+        visible
+    }
+
+    fn get_tile(&self, loc : Vec2<usize>) -> &Tile {
         &self.tiles[loc.x + loc.y * self.size.x]
     }
 
@@ -263,4 +1140,143 @@ impl Room {
     fn get_tile_type(&self, loc : Vec2<usize>) -> TileType {
         self.tiles[loc.x + loc.y * self.size.x].id
     }
-}
\ No newline at end of file
+}
+
+// Letters prefabs can use to place monsters
+pub fn is_monster_glyph(glyph : char) -> bool {
+    match glyph {
+        'g' | 'a' | 's' | 'j' | 't' | 'b' => true,
+        _ => false
+    }
+}
+
+// What each monster takes out of a room's budget
+fn monster_cost(glyph : char) -> usize {
+    match glyph {
+        'g' | 'b' => 1,
+        'a' | 's' => 2,
+        'j' => 3,
+        't' => 4,
+        _ => 1
+    }
+}
+
+fn spawn_monster(glyph : char, pos : Vec2<usize>) -> Option<Box<Entity>> {
+    match glyph {
+        'g' => Some(Box::new(Goblin::new(pos))),
+        'a' => Some(Box::new(Archer::new(pos))),
+        's' => Some(Box::new(Shaman::new(pos))),
+        'j' => Some(Box::new(Slime::new(pos))),
+        't' => Some(Box::new(Troll::new(pos))),
+        'b' => Some(Box::new(Bat::new(pos))),
+        _ => None
+    }
+}
+
+// Somewhere next to origin a monster could be put
+fn open_neighbor(tiles : &Vec<Tile>, size : Vec2<usize>, 
+                 origin : Vec2<usize>, player : &Player) -> Option<Vec2<usize>> 
+{
+    let mut direction = Direction::North;
+    for _ in 0..4 {
+        if let Some(pos) = direction.try_apply(origin) {
+            if pos.x < size.x && pos.y < size.y && 
+               tiles[pos.x + pos.y * size.x].walkable() && !player.collision(pos) 
+            {
+                return Some(pos);
+            }
+        }
+        direction.rotate_cw();
+    }
+
+    None
+}
+
+// Plain floor that nothing is standing on
+fn free_floor(tiles : &Vec<Tile>) -> Vec<usize> {
+    (0..tiles.len()).filter(|i| match tiles[*i].id {
+                        TileType::Granite => !tiles[*i].occupied,
+                        _ => false
+                    })
+                    .collect()
+}
+
+fn open_ground(tile_type : TileType) -> bool {
+    match tile_type {
+        TileType::Rock | TileType::Wall => false,
+        _ => true
+    }
+}
+
+// Any rock touching open ground becomes wall
+fn build_walls(tiles : &mut Vec<Tile>, size : Vec2<usize>) {
+    for i in 0..tiles.len() {
+        if let TileType::Rock = tiles[i].id {
+            let x = i % size.x;
+            let y = i / size.x;
+            let mut touches_ground = false;
+            for ny in y.saturating_sub(1)..(y+2).min(size.y) {
+                for nx in x.saturating_sub(1)..(x+2).min(size.x) {
+                    if open_ground(tiles[nx + ny * size.x].id) {
+                        touches_ground = true;
+                    }
+                }
+            }
+
+            if touches_ground {
+                tiles[i].id = TileType::Wall;
+            }
+        }
+    }
+}
+
+// Sprinkle some terrain over the floor of a fresh room.
+// Only ever replaces granite so the walls stay intact
+fn add_terrain(tiles : &mut Vec<Tile>, size : Vec2<usize>, shape : RoomShape) {
+    let mut rng = rng();
+
+    // Split wide rooms in two with a wall and a door
+    if shape == RoomShape::Rectangle && size.x >= 9 && rng.gen_range(0, 4) == 0 {
+        let wall_x = rng.gen_range(3, size.x-3);
+        let door_y = rng.gen_range(1, size.y-1);
+        for y in 1..size.y-1 {
+            tiles[wall_x + y * size.x].id = if y == door_y {
+                TileType::Door{open : false}
+            }
+            else {
+                TileType::Wall
+            };
+        }
+    }
+
+    let features = [
+        (TileType::Rubble, 3, 3),
+        (TileType::DeepWater, 1, 6),
+        (TileType::Lava, 1, 4),
+        (TileType::Trap{hidden : true}, 2, 1),
+    ];
+
+    for &(tile_type, max_patches, patch_size) in features.iter() {
+        let patches = rng.gen_range(0, max_patches + 1);
+        for _ in 0..patches {
+            let mut pos = Vec2::new(rng.gen_range(1, size.x-1),
+                                    rng.gen_range(1, size.y-1));
+            // Random walk so the patch looks like a puddle
+            for _ in 0..patch_size {
+                let tile = &mut tiles[pos.x + pos.y * size.x];
+                if let TileType::Granite = tile.id {
+                    tile.id = tile_type;
+                }
+
+                let direction = Direction::get_direction(rng.gen_range(1, 5));
+                if let Some(next) = direction.try_apply(pos) {
+                    if next.x > 0 && next.y > 0 && 
+                       next.x < size.x-1 && next.y < size.y-1 
+                    {
+                        pos = next;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+extern crate rand;
+
+use std::collections::VecDeque;
+use self::rand::prelude::*;
+use utils::rng;
+
+use utils::Vec2;
+
+const CAVE_FILL_PERCENT : usize = 45;
+const CAVE_ITERATIONS : usize = 4;
+const CAVE_ATTEMPTS : usize = 5;
+
+// The outline of a room inside its bounding box.
+// carve() gives back a floor mask, true meaning floor. The outer 
+// ring of the box is never floor so there is always room for walls
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RoomShape {
+    Rectangle,
+    Cross,
+    L,
+    Circle,
+    PillaredHall,
+    Cave,
+    // Never rolled, prefabs bring their own layout
+    Prefab
+}
+
+impl RoomShape {
+    pub fn get_shape(val : usize) -> RoomShape {
+        match val {
+            1 => RoomShape::Cross,
+            2 => RoomShape::L,
+            3 => RoomShape::Circle,
+            4 => RoomShape::PillaredHall,
+            5 => RoomShape::Cave,
+            _ => RoomShape::Rectangle   // by default a rectangle
+        }
+    }
+
+    pub fn count() -> usize {
+        6
+    }
+
+    // Small rooms dont have the space for anything fancy
+    pub fn random(size : Vec2<usize>) -> RoomShape {
+        if size.x < 7 || size.y < 7 {
+            return RoomShape::Rectangle;
+        }
+
+        RoomShape::get_shape(rng().gen_range(0, RoomShape::count()))
+    }
+
+    pub fn carve(&self, size : Vec2<usize>) -> Vec<bool> {
+        let interior = |x : usize, y : usize| {
+            x > 0 && y > 0 && x < size.x-1 && y < size.y-1
+        };
+
+        let mut floor = vec![false; size.x * size.y];
+        match *self {
+            RoomShape::Rectangle | RoomShape::PillaredHall | RoomShape::Prefab => {
+                for i in 0..floor.len() {
+                    floor[i] = interior(i % size.x, i / size.x);
+                }
+
+                if *self == RoomShape::PillaredHall {
+                    // Single pillars with gaps between them can never
+                    // cut the floor in two
+                    for y in (2..size.y-2).filter(|y| y % 3 == 2) {
+                        for x in (2..size.x-2).filter(|x| x % 3 == 2) {
+                            floor[x + y * size.x] = false;
+                        }
+                    }
+                }
+            },
+            RoomShape::Cross => {
+                let (left, right) = (size.x / 3, size.x - 1 - size.x / 3);
+                let (top, bottom) = (size.y / 3, size.y - 1 - size.y / 3);
+                for i in 0..floor.len() {
+                    let x = i % size.x;
+                    let y = i / size.x;
+                    let vertical = x >= left && x <= right;
+                    let horizontal = y >= top && y <= bottom;
+                    floor[i] = interior(x, y) && (vertical || horizontal);
+                }
+            },
+            RoomShape::L => {
+                // Cut one of the corners out
+                let corner = rng().gen_range(0, 4);
+                let cut_x = size.x / 2;
+                let cut_y = size.y / 2;
+                for i in 0..floor.len() {
+                    let x = i % size.x;
+                    let y = i / size.x;
+                    let in_cut = match corner {
+                        0 => x < cut_x && y < cut_y,
+                        1 => x >= cut_x && y < cut_y,
+                        2 => x >= cut_x && y >= cut_y,
+                        _ => x < cut_x && y >= cut_y
+                    };
+                    floor[i] = interior(x, y) && !in_cut;
+                }
+            },
+            RoomShape::Circle => {
+                let center_x = (size.x - 1) as f32 / 2.0;
+                let center_y = (size.y - 1) as f32 / 2.0;
+                let radius_x = (size.x - 2) as f32 / 2.0;
+                let radius_y = (size.y - 2) as f32 / 2.0;
+                for i in 0..floor.len() {
+                    let x = i % size.x;
+                    let y = i / size.x;
+                    let dx = (x as f32 - center_x) / radius_x;
+                    let dy = (y as f32 - center_y) / radius_y;
+                    floor[i] = interior(x, y) && dx * dx + dy * dy <= 1.0;
+                }
+            },
+            RoomShape::Cave => {
+                floor = carve_cave(size);
+            }
+        }
+
+        keep_largest_region(&mut floor, size);
+        floor
+    }
+}
+
+// Classic cellular automata cave. If it keeps coming out too
+// small just fall back to a plain rectangle
+fn carve_cave(size : Vec2<usize>) -> Vec<bool> {
+    let mut rng = rng();
+    let area = (size.x - 2) * (size.y - 2);
+
+    for _ in 0..CAVE_ATTEMPTS {
+        let mut floor = vec![false; size.x * size.y];
+        for y in 1..size.y-1 {
+            for x in 1..size.x-1 {
+                floor[x + y * size.x] = rng.gen_range(0, 100) >= CAVE_FILL_PERCENT;
+            }
+        }
+
+        for _ in 0..CAVE_ITERATIONS {
+            let mut next = vec![false; floor.len()];
+            for y in 1..size.y-1 {
+                for x in 1..size.x-1 {
+                    let mut walls = 0;
+                    for ny in y-1..y+2 {
+                        for nx in x-1..x+2 {
+                            if !floor[nx + ny * size.x] {
+                                walls += 1;
+                            }
+                        }
+                    }
+                    // Counts itself so a wall with 4 wall neighbors stays a wall
+                    next[x + y * size.x] = walls < 5;
+                }
+            }
+            floor = next;
+        }
+
+        keep_largest_region(&mut floor, size);
+        if floor.iter().filter(|f| **f).count() * 3 >= area {
+            return floor;
+        }
+    }
+
+    RoomShape::Rectangle.carve(size)
+}
+
+// Flood fill every region and throw away all but the biggest one
+// so every floor tile can reach every other floor tile
+fn keep_largest_region(floor : &mut Vec<bool>, size : Vec2<usize>) {
+    let mut region = vec![0; floor.len()];
+    let mut region_sizes = vec![0];
+
+    for start in 0..floor.len() {
+        if !floor[start] || region[start] != 0 {
+            continue;
+        }
+
+        let id = region_sizes.len();
+        let mut count = 0;
+        let mut queue = VecDeque::new();
+        region[start] = id;
+        queue.push_back(start);
+        while let Some(i) = queue.pop_front() {
+            count += 1;
+            let x = i % size.x;
+            let y = i / size.x;
+            let neighbors = [
+                (x > 0, i.wrapping_sub(1)),
+                (x < size.x-1, i + 1),
+                (y > 0, i.wrapping_sub(size.x)),
+                (y < size.y-1, i + size.x)
+            ];
+            for &(in_bounds, n) in neighbors.iter() {
+                if in_bounds && floor[n] && region[n] == 0 {
+                    region[n] = id;
+                    queue.push_back(n);
+                }
+            }
+        }
+        region_sizes.push(count);
+    }
+
+    let mut largest = 0;
+    for id in 1..region_sizes.len() {
+        if largest == 0 || region_sizes[id] > region_sizes[largest] {
+            largest = id;
+        }
+    }
+
+    for i in 0..floor.len() {
+        floor[i] = floor[i] && region[i] == largest;
+    }
+}
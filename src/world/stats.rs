@@ -0,0 +1,46 @@
+use utils::Graph;
+use world::Room;
+
+// Numbers for tuning the generator, see src/bin/dungeon_stats.rs
+pub struct FloorStats {
+    pub rooms : usize,
+    // Width and height of every room
+    pub room_sizes : Vec<(usize, usize)>,
+    pub goblins : Vec<usize>,
+    // Most rooms you have to walk through to get between any two
+    pub diameter : usize,
+    // Rooms with only one way in or out
+    pub dead_ends : usize
+}
+
+impl FloorStats {
+    pub fn new(floor : &Graph<Room>) -> FloorStats {
+        let mut room_sizes = Vec::new();
+        let mut goblins = Vec::new();
+        let mut diameter = 0;
+        let mut dead_ends = 0;
+
+        for (id, node) in floor.iter() {
+            let room = &node.data;
+            room_sizes.push((room.width(), room.height()));
+            goblins.push(room.get_entities().values()
+                                            .filter(|e| e.kind() == "goblin")
+                                            .count());
+
+            let farthest = floor.distances(id).iter().filter_map(|d| *d).max().unwrap_or(0);
+            diameter = diameter.max(farthest);
+
+            if node.neighbors().len() == 1 {
+                dead_ends += 1;
+            }
+        }
+
+        FloorStats {
+            rooms : floor.node_count(),
+            room_sizes,
+            goblins,
+            diameter,
+            dead_ends
+        }
+    }
+}
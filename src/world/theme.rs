@@ -0,0 +1,146 @@
+extern crate toml;
+
+use std::collections::HashMap;
+use utils::Vec3;
+use entity::DrawOutput;
+use world::{TileType, TileDisplay};
+
+// The "colorizer". Rooms pick a theme and the view asks the theme
+// what a tile or entity should look like. Anything a theme file
+// leaves out falls back to the defaults in TileType::value or
+// whatever the entity drew itself as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ThemeKind {
+    Crypt,
+    Cave,
+    Sewer
+}
+
+impl ThemeKind {
+    pub fn get_theme(val : usize) -> ThemeKind {
+        match val {
+            1 => ThemeKind::Cave,
+            2 => ThemeKind::Sewer,
+            _ => ThemeKind::Crypt   // by default crypt
+        }
+    }
+
+    pub fn count() -> usize {
+        3
+    }
+
+    fn source(&self) -> &'static str {
+        match *self {
+            ThemeKind::Crypt => include_str!("../assets/themes/crypt.toml"),
+            ThemeKind::Cave => include_str!("../assets/themes/cave.toml"),
+            ThemeKind::Sewer => include_str!("../assets/themes/sewer.toml"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GlyphEntry {
+    icon : Option<char>,
+    fg : Option<[u8; 3]>,
+    bg : Option<[u8; 3]>
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    name : String,
+    #[serde(default)]
+    tiles : HashMap<String, GlyphEntry>,
+    #[serde(default)]
+    entities : HashMap<String, GlyphEntry>
+}
+
+pub struct Theme {
+    name : String,
+    tiles : HashMap<String, GlyphEntry>,
+    entities : HashMap<String, GlyphEntry>
+}
+
+impl Theme {
+    pub fn load(kind : ThemeKind) -> Result<Theme, String> {
+        Theme::parse(kind.source())
+    }
+
+    pub fn parse(contents : &str) -> Result<Theme, String> {
+        let file : ThemeFile = match toml::from_str(contents) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Invalid theme file: {}", e))
+        };
+
+        Ok(Theme {
+            name : file.name,
+            tiles : file.tiles,
+            entities : file.entities
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tile(&self, tile : &TileType) -> TileDisplay {
+        let mut display = tile.value();
+        if let Some(entry) = self.tiles.get(tile.name()) {
+            if let Some(icon) = entry.icon {
+                display.icon = icon;
+            }
+            if let Some(fg) = entry.fg {
+                display.fg = Vec3::new(fg[0], fg[1], fg[2]);
+            }
+            if let Some(bg) = entry.bg {
+                display.bg = Vec3::new(bg[0], bg[1], bg[2]);
+            }
+        }
+
+        display
+    }
+
+    // kind is the same key the entity uses in the theme file
+    // ie "goblin", "corpse", "player"
+    pub fn entity(&self, kind : &str, output : DrawOutput) -> DrawOutput {
+        let mut output = output;
+        if let Some(entry) = self.entities.get(kind) {
+            if let Some(icon) = entry.icon {
+                output.icon = icon;
+            }
+            if let Some(fg) = entry.fg {
+                output.fg = Vec3::new(fg[0], fg[1], fg[2]);
+            }
+            if let Some(bg) = entry.bg {
+                output.bg = Vec3::new(bg[0], bg[1], bg[2]);
+            }
+        }
+
+        output
+    }
+}
+
+// All the themes loaded up front so drawing never touches the files
+pub struct ThemeSet {
+    themes : HashMap<ThemeKind, Theme>
+}
+
+impl ThemeSet {
+    pub fn new() -> ThemeSet {
+        let mut themes = HashMap::new();
+        for i in 0..ThemeKind::count() {
+            let kind = ThemeKind::get_theme(i);
+            match Theme::load(kind) {
+                Ok(theme) => { themes.insert(kind, theme); },
+                Err(e) => panic!("Failed to load {:?} theme: {}", kind, e)
+            }
+        }
+
+        ThemeSet {
+            themes
+        }
+    }
+
+    pub fn get(&self, kind : ThemeKind) -> &Theme {
+        &self.themes[&kind]
+    }
+}
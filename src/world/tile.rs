@@ -1,5 +1,6 @@
 use world::Direction;
 use utils::Vec3;
+use entity::{AttackType, ElementalType};
 
 // I need to rethink this tile structure
 // I think I put too much into tiletype instead of tile
@@ -9,45 +10,158 @@ pub struct TileDisplay {
     pub icon : char
 }
 
+// What happens to whoever steps onto the tile
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TileEffect {
+    Attack(AttackType, i32),
+    Extinguish
+}
+
+// Everything the room, monster pathing and fov need to know
+// about a tile. Always get this from TileType::properties so 
+// they all agree
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TileProperties {
+    pub passable : bool,
+    pub opaque : bool,
+    pub movement_cost : usize,
+    pub on_enter : Option<TileEffect>
+}
+
 #[derive(Debug)]
 pub enum TileType {
+    Rock,
     Wall,
     Granite,
     Exit {
         node_id : usize,
         exiting_direction : Direction
     },
+    Door {
+        open : bool
+    },
+    DeepWater,
+    Lava,
+    Rubble,
+    Trap {
+        hidden : bool
+    }
 }
 
 impl TileType {
-    // Maybe move this out into some sort of "colorizer" object
-    // that takes in the tile type and the dungeon "theme" to 
-    // determine what the appropraite color should be
+    // Default look for a tile. Themes override this, see Theme::tile
     pub fn value(&self) -> TileDisplay {
         match *self {
+            TileType::Rock => TileDisplay {
+                fg : Vec3::new(95,95,95),
+                bg : Vec3::new(95,95,95),
+                icon : ' '
+            },
             TileType::Wall => TileDisplay {
                 fg : Vec3::new(255, 255, 255),
                 bg : Vec3::new(95,95,95),
                 icon : '#'
             },
-            TileType::Granite => TileDisplay {
+            TileType::Granite | TileType::Trap{hidden : true} => TileDisplay {
                 fg : Vec3::new(255, 255, 255),
                 bg : Vec3::new(95,95,95),
                 icon : '.'
             },
-            TileType::Exit{node_id, exiting_direction} => TileDisplay {
+            TileType::Exit{..} => TileDisplay {
                 fg : Vec3::new(0, 0, 0),
                 bg : Vec3::new(255, 242, 0),
                 icon : ' '
             },
+            TileType::Door{open} => TileDisplay {
+                fg : Vec3::new(160, 110, 50),
+                bg : Vec3::new(95,95,95),
+                icon : if open { '\'' } else { '+' }
+            },
+            TileType::DeepWater => TileDisplay {
+                fg : Vec3::new(120, 170, 255),
+                bg : Vec3::new(20, 50, 140),
+                icon : '~'
+            },
+            TileType::Lava => TileDisplay {
+                fg : Vec3::new(255, 220, 0),
+                bg : Vec3::new(200, 50, 0),
+                icon : '~'
+            },
+            TileType::Rubble => TileDisplay {
+                fg : Vec3::new(170, 170, 170),
+                bg : Vec3::new(95,95,95),
+                icon : ':'
+            },
+            TileType::Trap{hidden : false} => TileDisplay {
+                fg : Vec3::new(255, 60, 60),
+                bg : Vec3::new(95,95,95),
+                icon : '^'
+            },
         }
     }
 
-    pub fn collidable(&self) -> bool {
+    // Key used to look the tile up in a theme file.
+    // Hidden traps have to look like the floor
+    pub fn name(&self) -> &str {
         match *self {
-            TileType::Wall => true,
-            TileType::Granite => false,
-            TileType::Exit{node_id, exiting_direction} => false,
+            TileType::Rock => "rock",
+            TileType::Wall => "wall",
+            TileType::Granite => "granite",
+            TileType::Exit{..} => "exit",
+            TileType::Door{open : true} => "open_door",
+            TileType::Door{open : false} => "door",
+            TileType::DeepWater => "deep_water",
+            TileType::Lava => "lava",
+            TileType::Rubble => "rubble",
+            TileType::Trap{hidden : true} => "granite",
+            TileType::Trap{hidden : false} => "trap",
+        }
+    }
+
+    pub fn properties(&self) -> TileProperties {
+        match *self {
+            TileType::Rock | TileType::Wall => TileProperties {
+                passable : false,
+                opaque : true,
+                movement_cost : 1,
+                on_enter : None
+            },
+            TileType::Granite | TileType::Exit{..} => TileProperties {
+                passable : true,
+                opaque : false,
+                movement_cost : 1,
+                on_enter : None
+            },
+            TileType::Door{open} => TileProperties {
+                passable : open,
+                opaque : !open,
+                movement_cost : 1,
+                on_enter : None
+            },
+            TileType::DeepWater => TileProperties {
+                passable : true,
+                opaque : false,
+                movement_cost : 2,
+                on_enter : Some(TileEffect::Extinguish)
+            },
+            TileType::Lava => TileProperties {
+                passable : true,
+                opaque : false,
+                movement_cost : 1,
+                on_enter : Some(TileEffect::Attack(AttackType::Elemental(ElementalType::Fire), 4))
+            },
+            TileType::Rubble => TileProperties {
+                passable : true,
+                opaque : false,
+                movement_cost : 2,
+                on_enter : None
+            },
+            TileType::Trap{..} => TileProperties {
+                passable : true,
+                opaque : false,
+                movement_cost : 1,
+                on_enter : Some(TileEffect::Attack(AttackType::Piercing, 3))
+            },
         }
     }
 }
@@ -76,4 +190,15 @@ impl Tile {
             corpses : Vec::new()
         }
     }
-}
\ No newline at end of file
+
+    // Monsters stay off anything that would hurt them. Water 
+    // and rubble are fine, they just cost more to cross
+    pub fn walkable(&self) -> bool {
+        let properties = self.id.properties();
+        let harmful = match properties.on_enter {
+            Some(TileEffect::Attack(..)) => true,
+            _ => false
+        };
+        properties.passable && !self.occupied && !harmful
+    }
+}
@@ -1,8 +1,10 @@
 use utils::{Vec2, Graph};
 use entity::{EntityMap, CorpseMap};
-use world::Dungeon;
+use item::FloorItem;
+use world::{Dungeon, Tile};
 use player::Player;
 use log::Log;
+use spell::Spell;
 
 pub enum WorldNode {
     DungeonNode(Dungeon)
@@ -67,6 +69,101 @@ impl World {
         }
     }
 
+    pub fn room_size(&self) -> Vec2<usize> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.room_size(),
+        }
+    }
+
+    pub fn movement_cost(&self, pos : Vec2<usize>) -> usize {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.movement_cost(pos),
+        }
+    }
+
+    pub fn field_of_view(&self, origin : Vec2<usize>) -> Vec<bool> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.field_of_view(origin),
+        }
+    }
+
+    pub fn projectile_path(&self, start : Vec2<usize>, end : Vec2<usize>, range : usize) -> Vec<Vec2<usize>> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.projectile_path(start, end, range),
+        }
+    }
+
+    pub fn fire_projectile(&mut self, 
+                           player : &mut Player, 
+                           end : Vec2<usize>, 
+                           spell : Option<Spell>,
+                           log : &mut Log) -> Vec<Vec2<usize>> 
+    {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.fire_projectile(player, end, spell, log)
+        }
+    }
+
+    pub fn find_path(&self, start : Vec2<usize>, end : Vec2<usize>) -> Option<Vec<Vec2<usize>>> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.find_path(start, end),
+        }
+    }
+
+    pub fn teleport(&mut self, player : &mut Player) -> bool {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.teleport(player)
+        }
+    }
+
+    pub fn reveal_traps(&mut self) -> usize {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.reveal_traps()
+        }
+    }
+
+    pub fn map_floor(&mut self) {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.map_floor()
+        }
+    }
+
+    pub fn close_door(&mut self, pos : Vec2<usize>) -> bool {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.close_door(pos)
+        }
+    }
+
+    pub fn loot_corpse(&mut self, pos : Vec2<usize>, player : &mut Player, log : &mut Log) -> bool {
+        let node_id = self.active_node;
+        let result = self.get_mut_node(node_id);
+        match result {
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.loot_corpse(pos, player, log)
+        }
+    }
+
+    pub fn tile(&self, pos : Vec2<usize>) -> Option<&Tile> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.active_room().tile(pos)
+        }
+    }
+
     pub fn get_corpses(&self) -> Option<&CorpseMap> {
         let result = self.get_node(self.active_node);
         match result {
@@ -74,6 +171,41 @@ impl World {
         }
     }
 
+    pub fn get_items(&self) -> Option<&Vec<FloorItem>> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => return Some(dungeon.get_items()),
+        }
+    }
+
+    pub fn floor_dot(&self) -> String {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.floor_dot(),
+        }
+    }
+
+    pub fn floor_ascii(&self) -> String {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.floor_ascii(),
+        }
+    }
+
+    pub fn fully_visited(&self) -> bool {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.fully_visited(),
+        }
+    }
+
+    pub fn explore_step(&self, pos : Vec2<usize>) -> Option<Vec2<usize>> {
+        let result = self.get_node(self.active_node);
+        match result {
+            WorldNode::DungeonNode(ref dungeon) => dungeon.explore_step(pos),
+        }
+    }
+
     pub fn get_entities(&self) -> Option<&EntityMap> {
         let result = self.get_node(self.active_node);
         match result {
@@ -90,11 +222,11 @@ impl World {
         }
     }
 
-    pub fn step(&mut self, player : &mut Player)  {
+    pub fn step(&mut self, player : &mut Player, log : &mut Log)  {
         let node_id = self.active_node;
         let result = self.get_mut_node(node_id);
         match result {
-            WorldNode::DungeonNode(ref mut dungeon) => dungeon.step(player)
+            WorldNode::DungeonNode(ref mut dungeon) => dungeon.step(player, log)
         }
     }
 